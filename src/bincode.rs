@@ -0,0 +1,66 @@
+use std::ops::Deref;
+
+use bincode::{
+    BorrowDecode, Decode, Encode,
+    de::{BorrowDecoder, Decoder, read::Reader},
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+};
+
+use crate::{interned::Interned, string::InternedStr};
+
+impl Encode for Interned {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.deref().deref().encode(encoder)
+    }
+}
+
+/// Interns the data straight from the input when it's a slice, and reads it into a temporary
+/// buffer otherwise
+impl<Context> Decode<Context> for Interned {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let len = u64::decode(decoder)?;
+        let len = usize::try_from(len).map_err(|_| DecodeError::OutsideUsizeRange(len))?;
+        decoder.claim_bytes_read(len)?;
+
+        if let Some(value) = decoder.reader().peek_read(len) {
+            let interned = Interned::new(value);
+            decoder.reader().consume(len);
+            return Ok(interned);
+        }
+
+        let mut value = vec![0; len];
+        decoder.reader().read(&mut value)?;
+        Ok(value.into())
+    }
+}
+
+impl<'de, Context> BorrowDecode<'de, Context> for Interned {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        <&[u8]>::borrow_decode(decoder).map(Interned::new)
+    }
+}
+
+impl Encode for InternedStr {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.as_interned().encode(encoder)
+    }
+}
+
+impl<Context> Decode<Context> for InternedStr {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Interned::decode(decoder)?
+            .try_into()
+            .map_err(|inner| DecodeError::Utf8 { inner })
+    }
+}
+
+impl<'de, Context> BorrowDecode<'de, Context> for InternedStr {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        <&str>::borrow_decode(decoder).map(InternedStr::new)
+    }
+}
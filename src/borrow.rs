@@ -1,13 +1,44 @@
 use std::{
     cmp::Ordering,
+    error::Error,
+    ffi::OsString,
+    fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
-    ops::Deref,
+    io::{self, Write},
+    ops::{Deref, Index},
+    path::PathBuf,
+    slice::SliceIndex,
+    str::{FromStr, Utf8Error},
 };
 
-use crate::{
-    interned::{self, Interned},
-    pool::POOL,
-};
+use crate::interned::{self, Interned};
+
+/// The error returned by [BorrowedInterned::parse]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError<E> {
+    /// The value isn't valid UTF-8
+    Utf8(Utf8Error),
+    /// The value is valid UTF-8, but failed to parse
+    Parse(E),
+}
+
+impl<E: Display> Display for ParseError<E> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Utf8(o) => Display::fmt(o, f),
+            Self::Parse(o) => Display::fmt(o, f),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for ParseError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Utf8(o) => Some(o),
+            Self::Parse(o) => Some(o),
+        }
+    }
+}
 
 #[derive(Eq)]
 #[repr(transparent)]
@@ -52,19 +83,199 @@ use crate::{
 pub struct BorrowedInterned([u8]);
 
 impl BorrowedInterned {
+    /// `value` must be the data of an entry, which is what [BorrowedInterned::intern] relies on
     pub(crate) fn new(value: &[u8]) -> &BorrowedInterned {
         unsafe { &*(value as *const [u8] as *const BorrowedInterned) }
     }
 
     /// Constructs back an [Interned] value from the given &[BorrowedInterned]
     ///
-    /// Note that using this function has almost the same performance penalty as using
-    /// [Interned::new]
+    /// Returns a new handle to the same entry, without looking it up in its pool - including
+    /// values that were not kept in their pool due to its memory limit (see
+    /// [OverflowBehavior::Unpooled])
+    ///
+    /// [OverflowBehavior::Unpooled]: crate::pool::OverflowBehavior::Unpooled
     pub fn intern(&self) -> Interned {
-        Interned::from_existing(
-            POOL.get_from_existing_ref(self.deref())
-                .expect("borrowed values must already exist in the pool"),
-        )
+        // only ever borrowed from an entry, which is kept alive by the borrow
+        Interned::from_existing(unsafe { crate::pool::entry_of(self) })
+    }
+
+    /// Constructs a new [Interned] holding the data of `self` followed by `suffix`
+    ///
+    /// The data is concatenated into a reusable per-thread buffer, so only inserting a new entry
+    /// allocates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let base = Interned::new(b"key/");
+    ///
+    /// assert_eq!(
+    ///     base.with_suffix(b"1700000000").as_ptr(),
+    ///     Interned::new(b"key/1700000000").as_ptr()
+    /// );
+    /// ```
+    pub fn with_suffix(&self, suffix: &[u8]) -> Interned {
+        crate::pool::with_buffer(|buffer| {
+            buffer.extend_from_slice(self);
+            buffer.extend_from_slice(suffix);
+        })
+    }
+
+    /// Constructs a new [Interned] holding `prefix` followed by the data of `self`, same as
+    /// [BorrowedInterned::with_suffix]
+    pub fn with_prefix(&self, prefix: &[u8]) -> Interned {
+        crate::pool::with_buffer(|buffer| {
+            buffer.extend_from_slice(prefix);
+            buffer.extend_from_slice(self);
+        })
+    }
+
+    /// Same as [Interned::to_ascii_lowercase_interned], where a value that has no uppercase
+    /// ASCII letters is returned by [BorrowedInterned::intern]
+    pub fn to_ascii_lowercase_interned(&self) -> Interned {
+        convert_ascii_case(self, u8::is_ascii_uppercase, u8::to_ascii_lowercase)
+            .unwrap_or_else(|| self.intern())
+    }
+
+    /// Same as [Interned::to_ascii_uppercase_interned], where a value that has no lowercase
+    /// ASCII letters is returned by [BorrowedInterned::intern]
+    pub fn to_ascii_uppercase_interned(&self) -> Interned {
+        convert_ascii_case(self, u8::is_ascii_lowercase, u8::to_ascii_uppercase)
+            .unwrap_or_else(|| self.intern())
+    }
+
+    /// Checks whether the data is valid UTF-8
+    ///
+    /// Note that [`<[u8]>::is_ascii`](slice::is_ascii) is available through [Deref] for checking
+    /// whether the data is ASCII
+    pub fn is_utf8(&self) -> bool {
+        std::str::from_utf8(self.deref()).is_ok()
+    }
+
+    /// Parses the value as UTF-8 into any type implementing [FromStr]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// assert_eq!(Interned::new(b"42").parse::<u32>(), Ok(42));
+    /// assert!(Interned::new(b"x").parse::<u32>().is_err());
+    /// ```
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError<T::Err>> {
+        std::str::from_utf8(self.deref())
+            .map_err(ParseError::Utf8)?
+            .parse()
+            .map_err(ParseError::Parse)
+    }
+
+    /// Constructs an owned [OsString] from the data
+    ///
+    /// On unix platforms any data is a valid [OsString], so this conversion is lossless and
+    /// round-trips with `From<&OsStr>`
+    ///
+    /// On other platforms the data is decoded as lossy UTF-8
+    pub fn to_os_string(&self) -> OsString {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            std::ffi::OsStr::from_bytes(self.deref()).to_owned()
+        }
+        #[cfg(not(unix))]
+        {
+            String::from_utf8_lossy(self.deref()).into_owned().into()
+        }
+    }
+
+    /// Constructs an owned [PathBuf] from the data
+    ///
+    /// See [BorrowedInterned::to_os_string] for the platform caveats
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use intern_mint::Interned;
+    ///
+    /// let interned = Interned::from(Path::new("/tmp/file.txt"));
+    ///
+    /// assert_eq!(interned.to_path_buf(), Path::new("/tmp/file.txt"));
+    /// ```
+    pub fn to_path_buf(&self) -> PathBuf {
+        self.to_os_string().into()
+    }
+
+    /// Writes the data to `writer`
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.deref())
+    }
+
+    /// Writes the length of the data as a little-endian [u64], followed by the data, to `writer`
+    ///
+    /// This is the framing of byte slices in `bincode` with `bincode::config::legacy()` (fixed-size
+    /// little-endian integers) - not its default configuration, which encodes the length as a
+    /// varint
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let mut buffer = Vec::new();
+    /// Interned::new(b"hi").write_len_prefixed(&mut buffer).unwrap();
+    ///
+    /// assert_eq!(buffer, b"\x02\0\0\0\0\0\0\0hi");
+    /// ```
+    pub fn write_len_prefixed<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&(self.len() as u64).to_le_bytes())?;
+        self.write_to(writer)
+    }
+
+    /// Returns the length of the prefix shared by the bytes of `self` and `other`
+    ///
+    /// The bytes are compared a word at a time, which pays off on long values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let a = Interned::new(b"interned");
+    /// let b = Interned::new(b"internal");
+    ///
+    /// assert_eq!(a.common_prefix_len(&b), 6);
+    /// ```
+    pub fn common_prefix_len(&self, other: &BorrowedInterned) -> usize {
+        const WORD: usize = size_of::<u64>();
+
+        if self == other {
+            return self.len();
+        }
+
+        let (a, b) = (self.deref(), other.deref());
+
+        let mut prefix = 0;
+        for (a, b) in a.chunks_exact(WORD).zip(b.chunks_exact(WORD)) {
+            // little-endian so the first differing byte is in the lowest differing bits
+            let a = u64::from_le_bytes(a.try_into().expect("chunk size is a word"));
+            let b = u64::from_le_bytes(b.try_into().expect("chunk size is a word"));
+            let diff = a ^ b;
+            if diff != 0 {
+                return prefix + (diff.trailing_zeros() / 8) as usize;
+            }
+            prefix += WORD;
+        }
+
+        prefix
+            + a[prefix..]
+                .iter()
+                .zip(&b[prefix..])
+                .take_while(|(a, b)| a == b)
+                .count()
     }
 
     /// The default [Hash] trait implementation for [BorrowedInterned] is to hash the pointer
@@ -128,12 +339,26 @@ impl Deref for BorrowedInterned {
     }
 }
 
+impl<I: SliceIndex<[u8]>> Index<I> for BorrowedInterned {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        self.deref().index(index)
+    }
+}
+
 impl PartialEq for BorrowedInterned {
     fn eq(&self, other: &Self) -> bool {
         std::ptr::addr_eq(self.as_ptr(), other.as_ptr())
     }
 }
 
+impl PartialEq<Interned> for BorrowedInterned {
+    fn eq(&self, other: &Interned) -> bool {
+        self.eq(other.deref())
+    }
+}
+
 impl Hash for BorrowedInterned {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.as_ptr().hash(state);
@@ -146,9 +371,19 @@ impl PartialOrd for BorrowedInterned {
     }
 }
 
+impl PartialOrd<Interned> for BorrowedInterned {
+    fn partial_cmp(&self, other: &Interned) -> Option<Ordering> {
+        Some(self.cmp(other.deref()))
+    }
+}
+
+/// Ordered by data, where values of the same data from different pools (which are not equal) are
+/// ordered by pointer, so only equal values compare as [Ordering::Equal]
 impl Ord for BorrowedInterned {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.deref().cmp(other.deref())
+        self.deref()
+            .cmp(other.deref())
+            .then_with(|| self.as_ptr().cmp(&other.as_ptr()))
     }
 }
 
@@ -159,3 +394,16 @@ impl ToOwned for BorrowedInterned {
         self.intern()
     }
 }
+
+/// Interns `value` with `convert` applied to every byte, or returns [None] if no byte
+/// `needs_conversion`
+pub(crate) fn convert_ascii_case(
+    value: &[u8],
+    needs_conversion: fn(&u8) -> bool,
+    convert: fn(&u8) -> u8,
+) -> Option<Interned> {
+    value
+        .iter()
+        .any(needs_conversion)
+        .then(|| crate::pool::with_buffer(|buffer| buffer.extend(value.iter().map(convert))))
+}
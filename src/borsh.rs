@@ -0,0 +1,34 @@
+use std::{
+    io::{self, Read, Write},
+    ops::Deref,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{interned::Interned, string::InternedStr};
+
+impl BorshSerialize for Interned {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.deref().deref().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for Interned {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Vec::<u8>::deserialize_reader(reader).map(|o| o.into())
+    }
+}
+
+impl BorshSerialize for InternedStr {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.as_interned().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for InternedStr {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Interned::deserialize_reader(reader)?
+            .try_into()
+            .map_err(|o| io::Error::new(io::ErrorKind::InvalidData, o))
+    }
+}
@@ -8,7 +8,7 @@ use std::{
 
 use bstr::{BStr, BString, ByteSlice};
 
-use crate::{borrow::BorrowedInterned, interned::Interned};
+use crate::{borrow::BorrowedInterned, interned::Interned, pool};
 
 impl BorrowedInterned {
     pub fn as_bstr(&self) -> &BStr {
@@ -26,6 +26,29 @@ impl BorrowedInterned {
     pub fn as_str(&self) -> Cow<'_, str> {
         self.as_bstr().to_str_lossy()
     }
+
+    /// Splits the value on `/` and interns each component, so the components of many paths under
+    /// the same directories are stored once
+    ///
+    /// Empty components (of a leading, trailing or repeated `/`) are skipped
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let components = Interned::new(b"/var/log//syslog").path_components();
+    ///
+    /// assert_eq!(components.len(), 3);
+    /// assert_eq!(&components[1][..], b"log");
+    /// ```
+    pub fn path_components(&self) -> Vec<Interned> {
+        let components = self
+            .split_str("/")
+            .filter(|o| !o.is_empty())
+            .collect::<Vec<_>>();
+        pool::intern_many(&components)
+    }
 }
 
 impl Display for Interned {
@@ -0,0 +1,46 @@
+use std::ops::Deref;
+
+use ::bytes::Bytes;
+
+use crate::interned::Interned;
+
+/// Keeps the entry alive for as long as the [Bytes] sharing its data
+struct Owner(Interned);
+
+impl AsRef<[u8]> for Owner {
+    fn as_ref(&self) -> &[u8] {
+        self.0.deref()
+    }
+}
+
+impl Interned {
+    /// Returns a [Bytes] sharing the data of the entry without copying it, which keeps the entry
+    /// alive until it's dropped
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let interned = Interned::new(b"payload");
+    /// let bytes = interned.to_bytes();
+    ///
+    /// assert_eq!(bytes.as_ptr(), interned.as_ptr());
+    /// assert_eq!(Interned::from(bytes).as_ptr(), interned.as_ptr());
+    /// ```
+    pub fn to_bytes(&self) -> Bytes {
+        Bytes::from_owner(Owner(self.clone()))
+    }
+}
+
+impl From<Bytes> for Interned {
+    fn from(value: Bytes) -> Self {
+        Interned::new(&value)
+    }
+}
+
+impl From<&Bytes> for Interned {
+    fn from(value: &Bytes) -> Self {
+        Interned::new(value)
+    }
+}
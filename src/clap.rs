@@ -0,0 +1,33 @@
+use std::ffi::OsStr;
+
+use clap::{
+    Arg, Command, Error,
+    builder::{TypedValueParser, ValueParserFactory},
+};
+
+use crate::interned::Interned;
+
+/// A [TypedValueParser] interning the raw argument, including arguments that aren't valid UTF-8
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InternedValueParser;
+
+impl TypedValueParser for InternedValueParser {
+    type Value = Interned;
+
+    fn parse_ref(
+        &self,
+        _cmd: &Command,
+        _arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Interned, Error> {
+        Ok(value.into())
+    }
+}
+
+impl ValueParserFactory for Interned {
+    type Parser = InternedValueParser;
+
+    fn value_parser() -> Self::Parser {
+        InternedValueParser
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Formatter},
+};
+
+use lz4_flex::block::{compress_into, decompress_size_prepended, get_maximum_output_size};
+
+use crate::{
+    interned::Interned,
+    pool::{POOL, Pool, with_buffer_into},
+};
+
+const RAW: u8 = 0;
+const COMPRESSED: u8 = 1;
+
+/// The size from which [CompressedInterned::new] compresses values
+pub const DEFAULT_THRESHOLD: usize = 4096;
+
+/// An [Interned] that keeps large values compressed with LZ4 in the pool, and decompresses them
+/// on access
+///
+/// Values smaller than the threshold, or that don't get smaller when compressed, are kept as is
+/// and returned without copying
+///
+/// Values are deduplicated by their stored bytes, so the same value interned with thresholds that
+/// compress it in one case and not in the other are not equal
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::CompressedInterned;
+///
+/// let script = b"echo hello\n".repeat(1000);
+/// let a = CompressedInterned::new(&script);
+/// let b = CompressedInterned::new(&script);
+///
+/// assert_eq!(a, b);
+/// assert!(a.is_compressed());
+/// assert!(a.stored_len() < script.len());
+/// assert_eq!(&*a.get(), &script[..]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CompressedInterned(Interned);
+
+impl CompressedInterned {
+    /// Constructs a new [CompressedInterned] for a given `value`, compressing it if it's at least
+    /// [DEFAULT_THRESHOLD] bytes long
+    pub fn new(value: &[u8]) -> Self {
+        Self::with_threshold(value, DEFAULT_THRESHOLD)
+    }
+
+    /// Constructs a new [CompressedInterned] for a given `value`, compressing it if it's at least
+    /// `threshold` bytes long
+    pub fn with_threshold(value: &[u8], threshold: usize) -> Self {
+        Self::with_threshold_in(&POOL, value, threshold)
+    }
+
+    /// Same as [CompressedInterned::with_threshold], interning in the given `pool`
+    pub(crate) fn with_threshold_in(pool: &'static Pool, value: &[u8], threshold: usize) -> Self {
+        // the stored bytes are interned as is, as the pool's canonicalization would corrupt them
+        let stored = with_buffer_into(
+            |buffer| {
+                if value.len() >= threshold && Self::compress(value, buffer) {
+                    return;
+                }
+
+                buffer.clear();
+                buffer.push(RAW);
+                buffer.extend_from_slice(value);
+            },
+            |o| pool.intern_raw(o),
+        );
+        Self(stored)
+    }
+
+    /// Writes the compressed `value` into `buffer`, returns false if it didn't get smaller
+    fn compress(value: &[u8], buffer: &mut Vec<u8>) -> bool {
+        let Ok(len) = u32::try_from(value.len()) else {
+            return false;
+        };
+
+        buffer.push(COMPRESSED);
+        buffer.extend_from_slice(&len.to_le_bytes());
+        let start = buffer.len();
+        buffer.resize(start + get_maximum_output_size(value.len()), 0);
+
+        let written = compress_into(value, &mut buffer[start..])
+            .expect("buffer is resized to the maximum output size");
+        buffer.truncate(start + written);
+        buffer.len() - 1 < value.len()
+    }
+
+    /// Returns the value, decompressing it if needed
+    pub fn get(&self) -> Cow<'_, [u8]> {
+        let (tag, data) = self.0[..]
+            .split_first()
+            .expect("entries always start with a tag");
+        match *tag {
+            COMPRESSED => Cow::Owned(
+                decompress_size_prepended(data).expect("entries are compressed by this type"),
+            ),
+            _ => Cow::Borrowed(data),
+        }
+    }
+
+    /// Returns true if the value is kept compressed in the pool
+    pub fn is_compressed(&self) -> bool {
+        self.0[0] == COMPRESSED
+    }
+
+    /// Returns the number of bytes the value takes in the pool
+    pub fn stored_len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the underlying [Interned], holding the stored bytes rather than the value
+    pub fn as_interned(&self) -> &Interned {
+        &self.0
+    }
+}
+
+impl Debug for CompressedInterned {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Debug::fmt(&String::from_utf8_lossy(&self.get()), f)
+    }
+}
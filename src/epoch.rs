@@ -0,0 +1,163 @@
+//! A lock-free read path in front of the shards of a pool, using [crossbeam_epoch] to defer the
+//! release of removed entries until no lookup can still be reading them
+//!
+//! Each shard has a small direct-mapped cache of the entries it returned last, and looking up an
+//! entry that is cached never locks its shard - which is where read-heavy workloads (the same few
+//! thousand values interned millions of times) used to contend
+//!
+//! The cache doesn't hold a reference count of its entries, so the counts that decide when an
+//! entry is removed are the same with or without this feature
+//!
+//! Values whose cache slot is taken by another hot value still lock their shard, so when the set
+//! of hot values is large, the cache can be enlarged with [PoolBuilder::read_cache_slots]
+//!
+//! The shards themselves stay behind locks rather than being replaced by a lock-free map -
+//! removing an entry when its last [Interned] is dropped relies on checking the reference count
+//! and removing the entry while no other thread can insert the same data, and lookups that race
+//! with a removal are the rare case the cache falls back to the lock for
+//!
+//! [PoolBuilder::read_cache_slots]: crate::pool::PoolBuilder::read_cache_slots
+//! [Interned]: crate::Interned
+
+use std::{
+    mem::ManuallyDrop,
+    sync::atomic::{Ordering, fence},
+};
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned, Shared};
+
+use crate::pool::Handle;
+
+pub(crate) const DEFAULT_SLOTS_PER_SHARD: usize = 256;
+
+/// A copy of an entry's handle that doesn't own a reference count, the pool's own handle keeps
+/// the entry alive
+struct Cached<E>(ManuallyDrop<E>);
+
+pub(crate) struct ReadCache<E> {
+    slots_per_shard: usize,
+    slots: Box<[Atomic<Cached<E>>]>,
+}
+
+impl<E> ReadCache<E> {
+    pub(crate) fn new(shard_amount: usize, slots_per_shard: usize) -> Self {
+        assert!(
+            slots_per_shard > 0,
+            "read cache must have at least one slot per shard"
+        );
+
+        Self {
+            slots_per_shard,
+            slots: (0..shard_amount * slots_per_shard)
+                .map(|_| Atomic::null())
+                .collect(),
+        }
+    }
+
+    /// Slots are never shared between shards, so each slot is only written under the lock of its
+    /// shard
+    fn slot(&self, shard: usize, hash: u64) -> &Atomic<Cached<E>> {
+        &self.slots[shard * self.slots_per_shard + (hash as usize % self.slots_per_shard)]
+    }
+}
+
+impl<E: Handle> ReadCache<E> {
+    /// Returns the cached entry holding `value` without locking its shard
+    ///
+    /// `Err` is returned when the entry was cloned while it was being removed from the pool, in
+    /// which case the clone must be released (and dropped through [Handle::wipe_if_unique], since
+    /// it may be the last reference) and the lookup retried under the lock
+    pub(crate) fn get(&self, shard: usize, hash: u64, value: &E::Target) -> Option<Result<E, E>> {
+        let guard = epoch::pin();
+        let slot = self.slot(shard, hash);
+
+        let cached = slot.load(Ordering::Acquire, &guard);
+        // removed entries are only released once every thread that was pinned is done, so the
+        // entry is still alive even if it was removed since
+        let entry = unsafe { cached.as_ref() }?;
+        if entry.0.target() != value {
+            return None;
+        }
+
+        let cloned = E::clone(&entry.0);
+        // pairs with the fence in `try_remove` - either the entry is seen unpublished here, or
+        // the removal sees the count of the clone and keeps the entry
+        fence(Ordering::SeqCst);
+        Some(if slot.load(Ordering::Relaxed, &guard) == cached {
+            Ok(cloned)
+        } else {
+            Err(cloned)
+        })
+    }
+
+    /// Caches `entry`, which must be in the pool, called under the lock of its shard
+    ///
+    /// The thread is already pinned by the [ShardGuard] of the shard, so pinning here never runs
+    /// the releases deferred by other threads
+    ///
+    /// [ShardGuard]: crate::pool::ShardGuard
+    pub(crate) fn publish(&self, shard: usize, hash: u64, entry: &E) {
+        let guard = epoch::pin();
+        let slot = self.slot(shard, hash);
+
+        let current = slot.load(Ordering::Relaxed, &guard);
+        if unsafe { current.as_ref() }
+            .is_some_and(|o| std::ptr::addr_eq(o.0.target(), entry.target()))
+        {
+            return;
+        }
+
+        let copy = Cached(ManuallyDrop::new(unsafe { std::ptr::read(entry) }));
+        let previous = slot.swap(Owned::new(copy), Ordering::Release, &guard);
+        if !previous.is_null() {
+            unsafe { guard.defer_destroy(previous) };
+        }
+    }
+
+    /// Called under the lock of the entry's shard right before it's removed from the pool -
+    /// returns false when the entry has more than `max_count` references and must stay
+    ///
+    /// Same as [ReadCache::publish], the thread is already pinned, so the release deferred here
+    /// only runs once the shard is unlocked
+    pub(crate) fn try_remove(&self, shard: usize, hash: u64, entry: &E, max_count: usize) -> bool {
+        let guard = epoch::pin();
+        let slot = self.slot(shard, hash);
+
+        let current = slot.load(Ordering::Relaxed, &guard);
+        let cached = unsafe { current.as_ref() }
+            .is_some_and(|o| std::ptr::addr_eq(o.0.target(), entry.target()));
+        if cached {
+            slot.store(Shared::null(), Ordering::Relaxed);
+        }
+
+        // pairs with the fence in `get`
+        fence(Ordering::SeqCst);
+        if entry.strong_count() > max_count {
+            if cached {
+                slot.store(current, Ordering::Release);
+            }
+            return false;
+        }
+
+        if cached {
+            unsafe { guard.defer_destroy(current) };
+        }
+        // lookups that read the entry before it was unpublished may still clone it
+        let mut keep_alive = entry.clone();
+        guard.defer(move || keep_alive.wipe_if_unique());
+        true
+    }
+}
+
+impl<E> Drop for ReadCache<E> {
+    fn drop(&mut self) {
+        // no lookup can be in progress once the pool itself is dropped
+        let guard = unsafe { epoch::unprotected() };
+        for slot in self.slots.iter() {
+            let cached = slot.swap(Shared::null(), Ordering::Relaxed, guard);
+            if !cached.is_null() {
+                drop(unsafe { cached.into_owned() });
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::{Debug, Formatter};
+
+use crate::{interned::Interned, value::InternedValue};
+
+#[derive(PartialEq, Eq, Hash)]
+struct Node {
+    parent: Option<HierarchicalInterned>,
+    component: Interned,
+}
+
+/// Interns `/` separated paths one component at a time, so paths sharing a prefix share the
+/// nodes of that prefix instead of each holding a copy of its bytes
+///
+/// Every node holds its last component and a handle to its parent node, and is interned the same
+/// as [InternedValue] - so equal paths share the same node, and are compared and hashed by pointer
+///
+/// Meant for millions of paths under the same few directories, at the cost of interning every
+/// component on construction and of rebuilding the bytes with [HierarchicalInterned::to_bytes]
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::HierarchicalInterned;
+///
+/// let a = HierarchicalInterned::new(b"/usr/lib/libc.so");
+/// let b = HierarchicalInterned::new(b"/usr/lib/libm.so");
+///
+/// assert_eq!(a.parent(), b.parent());
+/// assert_eq!(a, HierarchicalInterned::new(b"/usr/lib/libc.so"));
+/// assert_eq!(a.to_bytes(), b"/usr/lib/libc.so");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HierarchicalInterned(InternedValue<Node>);
+
+impl HierarchicalInterned {
+    const SEPARATOR: u8 = b'/';
+
+    /// Constructs a new [HierarchicalInterned] for a given `path`, interning each of its
+    /// components
+    ///
+    /// Empty components (of a leading, trailing or repeated `/`) are kept, so the bytes of the
+    /// path are always rebuilt as is
+    pub fn new(path: &[u8]) -> Self {
+        let mut components = path.split(|&o| o == Self::SEPARATOR);
+        let first = components
+            .next()
+            .expect("split always yields at least one component");
+        components.fold(Self::root(first), |parent, o| parent.join(o))
+    }
+
+    fn root(component: &[u8]) -> Self {
+        Self(InternedValue::new(Node {
+            parent: None,
+            component: Interned::new(component),
+        }))
+    }
+
+    /// Constructs the path of `component` under `self`
+    ///
+    /// `component` is not split, so it may contain a `/` of its own - in which case the path is
+    /// not equal to the same path constructed with [HierarchicalInterned::new]
+    pub fn join(&self, component: &[u8]) -> Self {
+        Self(InternedValue::new(Node {
+            parent: Some(self.clone()),
+            component: Interned::new(component),
+        }))
+    }
+
+    /// Returns the path without its last component, or [None] if the path has a single component
+    pub fn parent(&self) -> Option<&HierarchicalInterned> {
+        self.0.parent.as_ref()
+    }
+
+    /// Returns the last component of the path
+    pub fn component(&self) -> &Interned {
+        &self.0.component
+    }
+
+    /// Returns the number of components of the path
+    pub fn depth(&self) -> usize {
+        self.ancestors().count()
+    }
+
+    /// Iterates over the path and its parents, from the path itself up to its first component
+    pub fn ancestors(&self) -> impl Iterator<Item = &HierarchicalInterned> {
+        std::iter::successors(Some(self), |o| o.parent())
+    }
+
+    /// Rebuilds the bytes of the path
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut components = self.ancestors().map(|o| o.component()).collect::<Vec<_>>();
+        components.reverse();
+
+        let mut bytes = Vec::with_capacity(
+            components
+                .iter()
+                .map(|o| o.len() + 1)
+                .sum::<usize>()
+                .saturating_sub(1),
+        );
+        for (i, component) in components.iter().enumerate() {
+            if i > 0 {
+                bytes.push(Self::SEPARATOR);
+            }
+            bytes.extend_from_slice(component);
+        }
+        bytes
+    }
+
+    /// Interns the bytes of the path as a whole
+    pub fn to_interned(&self) -> Interned {
+        crate::pool::with_buffer(|buffer| {
+            buffer.extend_from_slice(&self.to_bytes());
+        })
+    }
+}
+
+impl Debug for HierarchicalInterned {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Debug::fmt(&String::from_utf8_lossy(&self.to_bytes()), f)
+    }
+}
@@ -0,0 +1,33 @@
+use idna::{
+    AsciiDenyList, Errors,
+    uts46::{Hyphens, Uts46},
+};
+
+use crate::interned::Interned;
+
+impl Interned {
+    /// Same as [Interned::new_domain], where punycode (`xn--`) labels are decoded to Unicode and
+    /// the name is mapped according to [UTS #46](https://www.unicode.org/reports/tr46/) first
+    ///
+    /// Fails when `value` isn't a valid domain name
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let domain = Interned::new_domain_unicode(b"xn--mnchen-3ya.DE.").unwrap();
+    ///
+    /// assert_eq!(&domain[..], "münchen.de".as_bytes());
+    /// assert_eq!(
+    ///     domain.as_ptr(),
+    ///     Interned::new_domain_unicode("München.de".as_bytes()).unwrap().as_ptr()
+    /// );
+    /// ```
+    pub fn new_domain_unicode(value: &[u8]) -> Result<Self, Errors> {
+        let (decoded, result) =
+            Uts46::new().to_unicode(value, AsciiDenyList::EMPTY, Hyphens::Allow);
+        result?;
+        Ok(Self::new_domain(decoded.as_bytes()))
+    }
+}
@@ -3,19 +3,27 @@ use std::{
     cmp::Ordering,
     ffi::{OsStr, OsString},
     hash::{Hash, Hasher},
-    ops::Deref,
+    io::{self, BufRead},
+    ops::{Deref, Index},
     path::{Path, PathBuf},
-    sync::LazyLock,
+    slice::SliceIndex,
+    string::FromUtf16Error,
+    sync::{Arc, LazyLock, atomic},
 };
 
-use triomphe::Arc;
-
-use crate::{borrow::BorrowedInterned, pool::POOL};
+use crate::{
+    borrow::{BorrowedInterned, convert_ascii_case},
+    pool::{Bytes, Handle, Header, MemoryLimitExceeded, POOL, Pool, TryReserveError},
+    weak::WeakInterned,
+};
 
-#[derive(Clone, Eq)]
+#[derive(Clone)]
 #[repr(transparent)]
 /// The main type offered by this crate, responsible for interning slices
-pub struct Interned(Arc<[u8]>);
+///
+/// A single pointer - the length of the slice and the pool it was interned in are stored in the
+/// shared allocation, in front of the data
+pub struct Interned(Bytes);
 
 impl Interned {
     /// Constructs a new [Interned] for a given `value`
@@ -31,12 +39,483 @@ impl Interned {
     /// assert_eq!(a.as_ptr(), b.as_ptr());
     /// ```
     pub fn new(value: &[u8]) -> Self {
-        Self(POOL.get_or_insert(value))
+        POOL.intern(value)
+    }
+
+    /// Constructs a new [Interned] for a given `value` in the given `pool` instead of the global one
+    ///
+    /// See [Pool] for an example
+    pub fn new_in(pool: &'static Pool, value: &[u8]) -> Self {
+        pool.intern(value)
+    }
+
+    /// Constructs a new [Interned] for a given `value`, returning an error instead of an unpooled
+    /// value when it doesn't fit in the memory limits of the pool (see [PoolBuilder::memory_limit]
+    /// and [PoolBuilder::max_entry_len])
+    ///
+    /// Entries that are only referenced by the pool are removed first when the pool is configured
+    /// with [OverflowBehavior::Evict]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::{Interned, pool::PoolBuilder};
+    ///
+    /// let pool = Box::leak(Box::new(PoolBuilder::new().memory_limit(8).build()));
+    ///
+    /// let a = Interned::try_new_limited_in(pool, b"8 bytes!").unwrap();
+    ///
+    /// assert!(Interned::try_new_limited_in(pool, b"9 bytes!!").is_err());
+    /// assert!(Interned::try_new_limited_in(pool, b"8 bytes!").is_ok());
+    /// ```
+    ///
+    /// [PoolBuilder::memory_limit]: crate::pool::PoolBuilder::memory_limit
+    /// [PoolBuilder::max_entry_len]: crate::pool::PoolBuilder::max_entry_len
+    /// [OverflowBehavior::Evict]: crate::pool::OverflowBehavior::Evict
+    pub fn try_new_limited(value: &[u8]) -> Result<Self, MemoryLimitExceeded> {
+        POOL.intern_limited(value)
+    }
+
+    /// Same as [Interned::try_new_limited], interning into the given `pool` instead of the global
+    /// one
+    pub fn try_new_limited_in(
+        pool: &'static Pool,
+        value: &[u8],
+    ) -> Result<Self, MemoryLimitExceeded> {
+        pool.intern_limited(value)
     }
 
-    pub(crate) fn from_existing(value: Arc<[u8]>) -> Self {
+    /// Returns the [Interned] holding `value` if it's already interned, without inserting it
+    /// otherwise
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let a = Interned::new(b"known");
+    ///
+    /// assert_eq!(Interned::existing(b"known").unwrap().as_ptr(), a.as_ptr());
+    /// assert!(Interned::existing(b"unknown").is_none());
+    /// ```
+    pub fn existing(value: &[u8]) -> Option<Self> {
+        POOL.get(value)
+    }
+
+    /// Same as [Interned::existing], looking in the given `pool` instead of the global one
+    pub fn existing_in(pool: &'static Pool, value: &[u8]) -> Option<Self> {
+        pool.get(value)
+    }
+
+    /// Constructs a new [Interned] for a given `value`, returning an error instead of aborting
+    /// when allocating the new entry or growing the pool fails
+    ///
+    /// Values that already exist in the pool never allocate, so this always succeeds for them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let a = Interned::try_new(b"hello").unwrap();
+    /// let b = Interned::new(b"hello");
+    ///
+    /// assert_eq!(a.as_ptr(), b.as_ptr());
+    /// ```
+    pub fn try_new(value: &[u8]) -> Result<Self, TryReserveError> {
+        let value = POOL.canonical(value);
+        Ok(match POOL.set.try_get_or_insert(&value, &POOL)? {
+            Ok(o) => Self(o),
+            Err(MemoryLimitExceeded) => POOL.intern(&value),
+        })
+    }
+
+    /// Constructs a new [Interned] holding the UTF-8 form of the given UTF-16 `data`
+    ///
+    /// Returns an error if `data` contains unpaired surrogates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let utf16 = "hello".encode_utf16().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(&Interned::from_utf16(&utf16).unwrap()[..], b"hello");
+    /// assert!(Interned::from_utf16(&[0xD800]).is_err());
+    /// ```
+    pub fn from_utf16(data: &[u16]) -> Result<Self, FromUtf16Error> {
+        String::from_utf16(data).map(Self::from)
+    }
+
+    /// Constructs a new [Interned] holding the UTF-8 form of the given UTF-16 `data`, replacing
+    /// unpaired surrogates with [char::REPLACEMENT_CHARACTER]
+    pub fn from_utf16_lossy(data: &[u16]) -> Self {
+        String::from_utf16_lossy(data).into()
+    }
+
+    /// Interns the concatenation of `parts`, such as composite keys built from several fields
+    ///
+    /// The parts are concatenated into a reusable per-thread buffer (see
+    /// [pool::intern_from_parts]), so only inserting a new entry allocates - the concatenation
+    /// still has to be contiguous, since the pool's hasher doesn't hash data split into parts the
+    /// same as the whole
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let key = Interned::concat(&[b"tenant", b"\0", b"/path"]);
+    ///
+    /// assert_eq!(key.as_ptr(), Interned::new(b"tenant\0/path").as_ptr());
+    /// ```
+    ///
+    /// [pool::intern_from_parts]: crate::pool::intern_from_parts
+    pub fn concat(parts: &[&[u8]]) -> Self {
+        crate::pool::intern_from_parts(parts)
+    }
+
+    /// Interns `parts` joined by `separator`, such as dotted metric names or slash-joined paths
+    ///
+    /// The parts are joined into a reusable per-thread buffer, so only inserting a new entry
+    /// allocates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let name = Interned::join(b".", &[b"http", b"requests", b"total"]);
+    ///
+    /// assert_eq!(&name[..], b"http.requests.total");
+    /// ```
+    pub fn join(separator: &[u8], parts: &[&[u8]]) -> Self {
+        crate::pool::with_buffer(|buffer| {
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    buffer.extend_from_slice(separator);
+                }
+                buffer.extend_from_slice(part);
+            }
+        })
+    }
+
+    /// Constructs a new [Interned] for the normalized form of the domain name `value` - with ASCII
+    /// letters lowercased and the trailing dot of a fully qualified name stripped
+    ///
+    /// Internationalized names can be decoded from punycode as well with
+    /// `Interned::new_domain_unicode` (with the `idna` feature)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let domain = Interned::new_domain(b"WWW.Example.COM.");
+    ///
+    /// assert_eq!(&domain[..], b"www.example.com");
+    /// assert_eq!(domain.as_ptr(), Interned::new_domain(b"www.example.com").as_ptr());
+    /// ```
+    pub fn new_domain(value: &[u8]) -> Self {
+        let value = value.strip_suffix(b".").unwrap_or(value);
+        convert_ascii_case(value, u8::is_ascii_uppercase, u8::to_ascii_lowercase)
+            .unwrap_or_else(|| Self::new(value))
+    }
+
+    /// Interns every line of `reader`, reading them into a single reusable buffer instead of
+    /// allocating a [String] per line
+    ///
+    /// Lines are split the same as [BufRead::lines] - on `\n` or `\r\n`, which are not included -
+    /// but don't have to be valid UTF-8
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let lines = Interned::read_lines(&b"a\nb\r\na"[..])
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(&lines[1][..], b"b");
+    /// assert_eq!(lines[0].as_ptr(), lines[2].as_ptr());
+    /// ```
+    pub fn read_lines(mut reader: impl BufRead) -> impl Iterator<Item = io::Result<Self>> {
+        let mut buffer = Vec::new();
+        std::iter::from_fn(move || {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => None,
+                Ok(_) => {
+                    let line = match buffer.strip_suffix(b"\n") {
+                        Some(line) => line.strip_suffix(b"\r").unwrap_or(line),
+                        None => &buffer,
+                    };
+                    Some(Ok(Self::new(line)))
+                }
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+
+    /// Permanently keeps the entry in its pool, even after all of its handles are dropped
+    ///
+    /// Meant for hot constants (such as well-known header names) that would otherwise be
+    /// removed from and inserted to the pool over and over again
+    ///
+    /// Pinning an entry more than once has no effect, and pinned entries are counted by
+    /// [Stats::pinned]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::{Interned, pool};
+    ///
+    /// let ptr = Interned::new(b"content-type").pin().as_ptr();
+    ///
+    /// assert!(Interned::new(b"content-type").is_pinned());
+    /// assert_eq!(Interned::new(b"content-type").as_ptr(), ptr);
+    /// ```
+    ///
+    /// [Stats::pinned]: crate::pool::Stats::pinned
+    pub fn pin(self) -> Self {
+        if !self.header().pinned.swap(true, atomic::Ordering::Relaxed) {
+            // the leaked reference keeps the strong count above the removal threshold
+            std::mem::forget(self.0.clone());
+            self.pool()
+                .set
+                .pinned
+                .fetch_add(1, atomic::Ordering::Relaxed);
+        }
+        self
+    }
+
+    /// Returns whether the entry was pinned with [Interned::pin]
+    pub fn is_pinned(&self) -> bool {
+        self.header().pinned.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Returns a [WeakInterned] of the entry, which doesn't keep it in the pool
+    pub fn downgrade(&self) -> WeakInterned {
+        WeakInterned::new(self)
+    }
+
+    /// Constructs a new [Interned] with the data of a [triomphe::Arc]
+    ///
+    /// Interns by content - the data is only copied if it's not in the pool yet, since entries
+    /// keep a header in front of the data that `value` doesn't have room for
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let arc = triomphe::Arc::<[u8]>::from(b"hello".as_slice());
+    ///
+    /// assert!(Interned::from_arc(arc.clone()) == Interned::new(b"hello"));
+    /// assert_eq!(Interned::new(b"hello").into_arc(), arc);
+    /// ```
+    pub fn from_arc(value: triomphe::Arc<[u8]>) -> Self {
+        Self::new(&value)
+    }
+
+    /// Copies the data into a [triomphe::Arc], which doesn't keep the entry in the pool
+    pub fn into_arc(self) -> triomphe::Arc<[u8]> {
+        triomphe::Arc::from(&self[..])
+    }
+
+    /// Returns a `&'static` view of the data while keeping `self` intact
+    ///
+    /// Note that this pins the entry (see [Interned::pin]), so its memory is never reclaimed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let interned = Interned::new(b"forever");
+    /// let data: &'static [u8] = interned.as_static();
+    /// drop(interned);
+    ///
+    /// assert_eq!(data, b"forever");
+    /// ```
+    pub fn as_static(&self) -> &'static [u8] {
+        let pinned = self.clone().pin();
+        let data = &pinned.0.slice as *const [u8];
+        // the reference count never reaches zero again, so the data lives forever
+        unsafe { &*data }
+    }
+
+    /// Splits off the first byte, returning it alongside an [Interned] of the remainder
+    ///
+    /// Returns [None] if the value is empty
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let (tag, rest) = Interned::new(b"\x01payload").split_first().unwrap();
+    ///
+    /// assert_eq!(tag, 1);
+    /// assert_eq!(rest.as_ptr(), Interned::new(b"payload").as_ptr());
+    /// ```
+    pub fn split_first(&self) -> Option<(u8, Interned)> {
+        let (&first, rest) = self.deref().split_first()?;
+        Some((first, Interned::new(rest)))
+    }
+
+    /// Splits off the last byte, returning it alongside an [Interned] of the remainder
+    ///
+    /// Returns [None] if the value is empty
+    pub fn split_last(&self) -> Option<(u8, Interned)> {
+        let (&last, rest) = self.deref().split_last()?;
+        Some((last, Interned::new(rest)))
+    }
+
+    /// Splits the value on the first occurrence of `separator`, interning both sides
+    ///
+    /// Returns [None] if `separator` doesn't exist in the value
+    pub fn split_once(&self, separator: u8) -> Option<(Interned, Interned)> {
+        let index = self.iter().position(|&o| o == separator)?;
+        Some((
+            Interned::new(&self[..index]),
+            Interned::new(&self[index + 1..]),
+        ))
+    }
+
+    /// Splits the value on the last occurrence of `separator`, interning both sides
+    ///
+    /// Returns [None] if `separator` doesn't exist in the value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let (name, extension) = Interned::new(b"a.b.c").rsplit_once(b'.').unwrap();
+    ///
+    /// assert_eq!(&name[..], b"a.b");
+    /// assert_eq!(&extension[..], b"c");
+    /// ```
+    pub fn rsplit_once(&self, separator: u8) -> Option<(Interned, Interned)> {
+        let index = self.iter().rposition(|&o| o == separator)?;
+        Some((
+            Interned::new(&self[..index]),
+            Interned::new(&self[index + 1..]),
+        ))
+    }
+
+    /// Splits the value on `separator` into at most `n` parts, interning each part
+    ///
+    /// The last part holds the remainder of the value
+    pub fn splitn(&self, n: usize, separator: u8) -> impl Iterator<Item = Interned> {
+        self.deref()
+            .deref()
+            .splitn(n, move |&o| o == separator)
+            .map(Interned::new)
+    }
+
+    /// Constructs a new [Interned] with every `from` byte replaced by `to`
+    ///
+    /// If `from` doesn't exist in the value, a clone of `self` is returned without touching the pool
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let path = Interned::new(b"dir\\file");
+    ///
+    /// assert_eq!(&path.replace_byte(b'\\', b'/')[..], b"dir/file");
+    /// ```
+    pub fn replace_byte(&self, from: u8, to: u8) -> Interned {
+        if !self.contains(&from) {
+            return self.clone();
+        }
+
+        self.iter()
+            .map(|&o| if o == from { to } else { o })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// Returns the [Interned] holding the data with ASCII letters converted to lowercase, see
+    /// [`<[u8]>::to_ascii_lowercase`](slice::to_ascii_lowercase)
+    ///
+    /// If the value has no uppercase ASCII letters, a clone of `self` is returned without touching
+    /// the pool
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let header = Interned::new(b"Content-Type");
+    ///
+    /// assert_eq!(&header.to_ascii_lowercase_interned()[..], b"content-type");
+    /// ```
+    pub fn to_ascii_lowercase_interned(&self) -> Interned {
+        convert_ascii_case(self, u8::is_ascii_uppercase, u8::to_ascii_lowercase)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Returns the [Interned] holding the data with ASCII letters converted to uppercase, see
+    /// [`<[u8]>::to_ascii_uppercase`](slice::to_ascii_uppercase)
+    ///
+    /// If the value has no lowercase ASCII letters, a clone of `self` is returned without touching
+    /// the pool
+    pub fn to_ascii_uppercase_interned(&self) -> Interned {
+        convert_ascii_case(self, u8::is_ascii_lowercase, u8::to_ascii_uppercase)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Constructs a new [Interned] with every non-overlapping occurrence of `from` replaced by `to`
+    ///
+    /// If `from` is empty or doesn't exist in the value, a clone of `self` is returned without
+    /// touching the pool
+    pub fn replace(&self, from: &[u8], to: &[u8]) -> Interned {
+        if from.is_empty() {
+            return self.clone();
+        }
+
+        let find = |o: &[u8]| o.windows(from.len()).position(|o| o == from);
+
+        let mut rest = self.deref().deref();
+        let Some(mut index) = find(rest) else {
+            return self.clone();
+        };
+
+        let mut replaced = Vec::with_capacity(self.len());
+        loop {
+            replaced.extend_from_slice(&rest[..index]);
+            replaced.extend_from_slice(to);
+            rest = &rest[index + from.len()..];
+
+            match find(rest) {
+                Some(next) => index = next,
+                None => break,
+            }
+        }
+        replaced.extend_from_slice(rest);
+
+        replaced.into()
+    }
+
+    pub(crate) fn from_existing(value: Bytes) -> Self {
         Self(value)
     }
+
+    pub(crate) fn header(&self) -> &Header {
+        &self.0.header.header
+    }
+
+    pub(crate) fn pool(&self) -> &'static Pool {
+        // always constructed from a `&'static Pool`
+        unsafe { &*self.header().pool }
+    }
 }
 
 pub(crate) static DEFAULT: LazyLock<Interned> = LazyLock::new(|| Interned::new(Default::default()));
@@ -49,7 +528,8 @@ impl Default for Interned {
 
 impl Drop for Interned {
     fn drop(&mut self) {
-        POOL.remove_if_needed(&self.0);
+        self.pool().release(&self.0);
+        self.0.wipe_if_unique();
     }
 }
 
@@ -57,7 +537,15 @@ impl Deref for Interned {
     type Target = BorrowedInterned;
 
     fn deref(&self) -> &Self::Target {
-        BorrowedInterned::new(self.0.deref())
+        BorrowedInterned::new(&self.0.slice)
+    }
+}
+
+impl<I: SliceIndex<[u8]>> Index<I> for Interned {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        self.deref().index(index)
     }
 }
 
@@ -67,6 +555,14 @@ impl PartialEq for Interned {
     }
 }
 
+impl Eq for Interned {}
+
+impl PartialEq<BorrowedInterned> for Interned {
+    fn eq(&self, other: &BorrowedInterned) -> bool {
+        self.deref().eq(other)
+    }
+}
+
 impl Hash for Interned {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.deref().hash(state)
@@ -79,6 +575,12 @@ impl PartialOrd for Interned {
     }
 }
 
+impl PartialOrd<BorrowedInterned> for Interned {
+    fn partial_cmp(&self, other: &BorrowedInterned) -> Option<Ordering> {
+        Some(self.deref().cmp(other))
+    }
+}
+
 impl Ord for Interned {
     fn cmp(&self, other: &Self) -> Ordering {
         self.deref().cmp(other)
@@ -150,6 +652,25 @@ impl From<&PathBuf> for Interned {
     }
 }
 
+impl From<Arc<[u8]>> for Interned {
+    fn from(value: Arc<[u8]>) -> Self {
+        value.deref().into()
+    }
+}
+
+impl From<&Arc<[u8]>> for Interned {
+    fn from(value: &Arc<[u8]>) -> Self {
+        value.deref().into()
+    }
+}
+
+/// Copies the data, as entries are kept in the pool's own allocations
+impl From<&Interned> for Arc<[u8]> {
+    fn from(value: &Interned) -> Self {
+        Arc::from(&value[..])
+    }
+}
+
 impl Borrow<BorrowedInterned> for Interned {
     fn borrow(&self) -> &BorrowedInterned {
         self.deref()
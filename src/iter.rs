@@ -0,0 +1,73 @@
+use std::iter::FusedIterator;
+
+use crate::interned::Interned;
+
+/// Adds [InternedIterExt::interned] to iterators of byte slices and strings
+pub trait InternedIterExt: Iterator + Sized
+where
+    Self::Item: AsRef<[u8]>,
+{
+    /// Interns every item of the iterator
+    ///
+    /// Runs of identical consecutive items are interned once, the following items are compared to
+    /// the previous one and reuse it without hashing them or looking in the pool - which is common
+    /// when deduplicating sorted or repetitive columns
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::{Interned, InternedIterExt};
+    ///
+    /// let lines = ["a", "a", "b", "a"];
+    /// let interned = lines.iter().interned().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(interned[0].as_ptr(), interned[1].as_ptr());
+    /// assert_eq!(interned[0].as_ptr(), interned[3].as_ptr());
+    /// assert_eq!(interned[2].as_ptr(), Interned::new(b"b").as_ptr());
+    /// ```
+    fn interned(self) -> InternedIter<Self> {
+        InternedIter {
+            iter: self,
+            last: None,
+        }
+    }
+}
+
+impl<I: Iterator> InternedIterExt for I where I::Item: AsRef<[u8]> {}
+
+/// The iterator returned by [InternedIterExt::interned]
+#[derive(Clone)]
+pub struct InternedIter<I> {
+    iter: I,
+    last: Option<Interned>,
+}
+
+impl<I: Iterator> Iterator for InternedIter<I>
+where
+    I::Item: AsRef<[u8]>,
+{
+    type Item = Interned;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let item = item.as_ref();
+
+        if let Some(last) = &self.last
+            && &last[..] == item
+        {
+            return Some(last.clone());
+        }
+
+        let interned = Interned::new(item);
+        self.last = Some(interned.clone());
+        Some(interned)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for InternedIter<I> where I::Item: AsRef<[u8]> {}
+
+impl<I: FusedIterator> FusedIterator for InternedIter<I> where I::Item: AsRef<[u8]> {}
@@ -0,0 +1,197 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Formatter},
+};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, SeqAccess, Visitor},
+};
+use serde_json::Number;
+
+use crate::{interned::Interned, string::InternedStr};
+
+/// The objects of a [Value], keyed by interned strings
+pub type Map = BTreeMap<InternedStr, Value>;
+
+/// A JSON value, the same as [serde_json::Value] with its strings and object keys interned
+///
+/// Documents with the same keys over and over again keep a single copy of each key, and cloning
+/// a [Value] never copies its strings
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::json::Value;
+///
+/// let a = serde_json::from_str::<Value>(r#"{"level":"info","msg":"started"}"#).unwrap();
+/// let b = serde_json::from_str::<Value>(r#"{"level":"info","msg":"stopped"}"#).unwrap();
+///
+/// let level = |o: &Value| o.get("level").unwrap().as_str().unwrap().as_ptr();
+/// assert_eq!(level(&a), level(&b));
+/// assert_eq!(serde_json::Value::from(a)["msg"], "started");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(InternedStr),
+    Array(Vec<Value>),
+    Object(Map),
+}
+
+impl Value {
+    /// Returns the value of `key` if this is an object that has it
+    ///
+    /// Keys that are not interned at all are never looked up in the object
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        let Value::Object(map) = self else {
+            return None;
+        };
+        let key = InternedStr::try_from(Interned::existing(key.as_bytes())?).ok()?;
+        map.get(&key)
+    }
+
+    /// Returns the string if this is a string
+    pub fn as_str(&self) -> Option<&InternedStr> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Value> for Value {
+    fn from(value: serde_json::Value) -> Self {
+        Value::from(&value)
+    }
+}
+
+impl From<&serde_json::Value> for Value {
+    fn from(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(value) => Value::Bool(*value),
+            serde_json::Value::Number(value) => Value::Number(value.clone()),
+            serde_json::Value::String(value) => Value::String(value.into()),
+            serde_json::Value::Array(values) => {
+                Value::Array(values.iter().map(Value::from).collect())
+            }
+            serde_json::Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        serde_json::Value::from(&value)
+    }
+}
+
+impl From<&Value> for serde_json::Value {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(value) => serde_json::Value::Bool(*value),
+            Value::Number(value) => serde_json::Value::Number(value.clone()),
+            Value::String(value) => serde_json::Value::String(value.to_string()),
+            Value::Array(values) => {
+                serde_json::Value::Array(values.iter().map(serde_json::Value::from).collect())
+            }
+            Value::Object(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.to_string(), value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Number(value) => value.serialize(serializer),
+            Value::String(value) => value.serialize(serializer),
+            Value::Array(values) => values.serialize(serializer),
+            Value::Object(map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Number::from_f64(v).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(4096));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = Map::new();
+        while let Some((key, value)) = map.next_entry::<InternedStr, Value>()? {
+            values.insert(key, value);
+        }
+        Ok(Value::Object(values))
+    }
+}
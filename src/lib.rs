@@ -1,14 +1,67 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "bincode")]
+pub mod bincode;
 pub mod borrow;
+#[cfg(feature = "borsh")]
+pub mod borsh;
 #[cfg(feature = "bstr")]
 pub mod bstr;
+#[cfg(feature = "bytes")]
+pub mod bytes;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "lz4")]
+pub mod compressed;
+#[cfg(feature = "epoch")]
+mod epoch;
+pub mod hierarchical;
+#[cfg(feature = "idna")]
+pub mod idna;
 pub mod interned;
+pub mod iter;
+#[cfg(feature = "json")]
+pub mod json;
+mod macros;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod pool;
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+#[cfg(feature = "secrecy")]
+pub mod secrecy;
 #[cfg(feature = "serde")]
 pub mod serde;
+#[cfg(feature = "sha2")]
+pub mod sha2;
+#[cfg(feature = "speedy")]
+pub mod speedy;
+pub mod string;
+#[cfg(feature = "subtle")]
+pub mod subtle;
+pub mod symbol;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+#[cfg(feature = "unicode-norm")]
+pub mod unicode_norm;
+pub mod value;
+pub mod weak;
 
-pub use crate::{borrow::BorrowedInterned, interned::Interned};
+pub use crate::{
+    borrow::BorrowedInterned,
+    hierarchical::HierarchicalInterned,
+    interned::Interned,
+    iter::InternedIterExt,
+    string::{BorrowedInternedStr, InternedStr},
+    symbol::{Symbol, SymbolTable},
+    value::InternedValue,
+    weak::WeakInterned,
+};
+
+#[cfg(feature = "lz4")]
+pub use crate::compressed::CompressedInterned;
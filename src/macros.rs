@@ -0,0 +1,50 @@
+/// Formats the arguments the same as [format!] and interns the result, without the temporary
+/// [String] of `Interned::from(format!(...))`
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, format_interned};
+///
+/// let (host, port) = ("localhost", 8080);
+/// let interned = format_interned!("{host}:{port}");
+///
+/// assert_eq!(interned.as_ptr(), Interned::new(b"localhost:8080").as_ptr());
+/// ```
+#[macro_export]
+macro_rules! format_interned {
+    ($($arg:tt)*) => {
+        $crate::pool::intern_fmt(::std::format_args!($($arg)*))
+    };
+}
+
+/// Interns `value` (a byte string or string literal) once per call site, keeping it in a
+/// lazily-initialized static - so hot literals don't go through the pool on every call
+///
+/// Returns a clone of the static [Interned], which keeps the value in the pool for the rest of the
+/// process
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, intern};
+///
+/// fn content_type() -> Interned {
+///     intern!(b"content-type")
+/// }
+///
+/// assert_eq!(content_type().as_ptr(), content_type().as_ptr());
+/// assert_eq!(content_type().as_ptr(), Interned::new(b"content-type").as_ptr());
+/// ```
+///
+/// [Interned]: crate::Interned
+#[macro_export]
+macro_rules! intern {
+    ($value:expr) => {{
+        static INTERNED: ::std::sync::LazyLock<$crate::Interned> =
+            ::std::sync::LazyLock::new(|| {
+                $crate::Interned::new(::std::convert::AsRef::<[u8]>::as_ref($value))
+            });
+        ::std::clone::Clone::clone(&*INTERNED)
+    }};
+}
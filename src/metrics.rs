@@ -0,0 +1,22 @@
+//! Reports the pools' activity through the [metrics] crate, to whichever recorder is installed
+//!
+//! The following metrics are emitted, aggregated across all the pools:
+//!
+//! - `intern_mint.insertions` - counter of the entries inserted to the pools
+//! - `intern_mint.removals` - counter of the entries removed from the pools
+//! - `intern_mint.pool.entries` - gauge of the number of entries in the pools
+//! - `intern_mint.pool.bytes` - gauge of the total size of the entries' data
+
+use metrics::{counter, gauge};
+
+pub(crate) fn record_insert(size: usize) {
+    counter!("intern_mint.insertions").increment(1);
+    gauge!("intern_mint.pool.entries").increment(1.0);
+    gauge!("intern_mint.pool.bytes").increment(size as f64);
+}
+
+pub(crate) fn record_remove(size: usize) {
+    counter!("intern_mint.removals").increment(1);
+    gauge!("intern_mint.pool.entries").decrement(1.0);
+    gauge!("intern_mint.pool.bytes").decrement(size as f64);
+}
@@ -1,11 +1,193 @@
-use std::{ops::Deref, sync::LazyLock};
+use std::{
+    alloc::Layout,
+    borrow::{Borrow, Cow},
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+};
 
-use hashbrown::HashTable;
-use parking_lot::{Mutex, MutexGuard};
-use triomphe::Arc;
+pub use ahash::RandomState;
+pub use hashbrown::TryReserveError;
+use hashbrown::{HashTable, hash_table::Entry};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use triomphe::{Arc, ThinArc};
 
-type LockedShard = HashTable<Arc<[u8]>>;
-type Shard = Mutex<LockedShard>;
+use crate::{BorrowedInterned, Interned};
+
+type LockedShard<E> = HashTable<E>;
+type Shard<E> = RwLock<LockedShard<E>>;
+
+/// The handles stored in a [ShardedSet], each pointing to the shared allocation of an entry
+pub(crate) trait Handle: Clone + Send + 'static {
+    type Target: ?Sized + Hash + Eq;
+
+    fn target(&self) -> &Self::Target;
+
+    fn strong_count(&self) -> usize;
+
+    /// The hash of the target, which must be the same as hashing it with `hash_builder`
+    fn data_hash(&self, hash_builder: &RandomState) -> u64 {
+        hash_builder.hash_one(self.target())
+    }
+
+    /// Called right before the handle is dropped, when it may be the last reference to its entry
+    fn wipe_if_unique(&mut self) {}
+}
+
+impl<T: ?Sized + Hash + Eq + Send + Sync + 'static> Handle for Arc<T> {
+    type Target = T;
+
+    fn target(&self) -> &Self::Target {
+        self.deref()
+    }
+
+    fn strong_count(&self) -> usize {
+        Arc::strong_count(self)
+    }
+}
+
+/// The lock guard of a shard
+///
+/// With the `epoch` feature, the thread is pinned before the shard is locked and unpinned only
+/// after it's unlocked - pinning may run the releases that other threads deferred, and those may
+/// drop the last reference to an entry (and any [Interned] it holds), which must never happen
+/// while a shard is locked
+///
+/// With the `tracing` feature, the events recorded while the shard is locked are emitted right
+/// after it's unlocked, see [crate::tracing]
+pub(crate) struct ShardGuard<G> {
+    guard: ManuallyDrop<G>,
+    #[cfg(feature = "epoch")]
+    _pin: crossbeam_epoch::Guard,
+}
+
+impl<G> ShardGuard<G> {
+    fn lock(lock: impl FnOnce() -> G) -> Self {
+        #[cfg(feature = "epoch")]
+        let pin = crossbeam_epoch::pin();
+        Self {
+            guard: ManuallyDrop::new(lock()),
+            #[cfg(feature = "epoch")]
+            _pin: pin,
+        }
+    }
+}
+
+impl<G> Drop for ShardGuard<G> {
+    fn drop(&mut self) {
+        // the thread is unpinned only after the fields are dropped, once the shard is unlocked
+        unsafe { ManuallyDrop::drop(&mut self.guard) };
+        #[cfg(feature = "tracing")]
+        crate::tracing::emit_pending();
+    }
+}
+
+impl<G: Deref> Deref for ShardGuard<G> {
+    type Target = G::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for ShardGuard<G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+/// Stored in front of the data in the allocation of every interned slice, so [Interned] can be
+/// a single pointer
+pub(crate) struct Header {
+    /// The pool that the slice was interned in
+    pub(crate) pool: *const Pool,
+    /// The hash of the slice by the pool's hash builder, so large slices aren't hashed again
+    /// when they are dropped or when the pool grows
+    pub(crate) hash: u64,
+    /// Set once the slice is pinned by [Interned::pin]
+    pub(crate) pinned: AtomicBool,
+}
+
+// the pointer above always comes from a `&'static Pool`, and [Pool] is [Sync]
+impl Header {
+    fn new(hash: u64, pool: &'static Pool) -> Self {
+        Self {
+            pool,
+            hash,
+            pinned: Default::default(),
+        }
+    }
+}
+
+/// The reference count and the header (including the slice's length) that precede the data of
+/// every entry
+const ENTRY_OVERHEAD: usize = size_of::<usize>() + size_of::<triomphe::HeaderWithLength<Header>>();
+
+#[cfg(test)]
+thread_local! {
+    /// Makes [ShardedSet::try_new_entry] fail as if the allocator ran out of memory
+    pub(crate) static FAIL_ENTRY_ALLOCATION: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+unsafe impl Send for Header {}
+unsafe impl Sync for Header {}
+
+pub(crate) type Bytes = ThinArc<Header, u8>;
+
+/// Returns a new handle to the entry holding `data`, without looking it up in its pool
+///
+/// # Safety
+///
+/// `data` must be the data of an entry, which must stay alive for the duration of the call
+pub(crate) unsafe fn entry_of(data: &[u8]) -> Bytes {
+    // the same for every entry, as they are all of the same type
+    static DATA_OFFSET: LazyLock<usize> = LazyLock::new(|| {
+        let entry = Bytes::from_header_and_slice(Header::new(0, &POOL), &[0]);
+        entry.slice.as_ptr() as usize - entry.ptr() as usize
+    });
+
+    let ptr = data.as_ptr().wrapping_sub(*DATA_OFFSET).cast();
+    // the handle doesn't own the reference count of the entry it's constructed from
+    let entry = ManuallyDrop::new(unsafe { Bytes::from_raw(ptr) });
+    Bytes::clone(&entry)
+}
+
+impl Handle for Bytes {
+    type Target = [u8];
+
+    fn target(&self) -> &Self::Target {
+        &self.slice
+    }
+
+    fn strong_count(&self) -> usize {
+        ThinArc::strong_count(self)
+    }
+
+    fn data_hash(&self, _: &RandomState) -> u64 {
+        self.header.header.hash
+    }
+
+    /// Wipes the data before it's freed, see the `zeroize` feature
+    #[cfg(feature = "zeroize")]
+    fn wipe_if_unique(&mut self) {
+        use zeroize::Zeroize;
+
+        self.with_arc_mut(|o| {
+            // no other handle exists, and none can be created once the entry left the pool
+            if let Some(o) = Arc::get_mut(o) {
+                o.slice_mut().zeroize();
+            }
+        });
+    }
+}
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct MemoryUsage {
@@ -13,66 +195,325 @@ pub struct MemoryUsage {
     pub capacity: usize,
 }
 
-pub(crate) struct ShardedSet {
+/// A detailed view of the entries of a pool, returned by [detailed_stats]
+#[derive(Debug, Default, Clone)]
+pub struct DetailedStats {
+    /// Number of entries in the pool
+    pub entries: usize,
+    /// Total length of the entries' data
+    pub data_bytes: usize,
+    /// Estimated heap memory used by the pool - the allocations of the entries (including their
+    /// reference counts and headers) and the shards' hash-tables
+    pub heap_bytes: usize,
+    /// Number of entries in each shard
+    pub shard_entries: Vec<usize>,
+    /// Length of the data of the largest entry
+    pub largest_entry: usize,
+    /// The counters returned by [stats]
+    pub counters: Stats,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Stats {
+    /// Number of lookups that found an existing entry in the pool
+    pub hits: usize,
+    /// Number of lookups that inserted a new entry to the pool
+    pub inserts: usize,
+    /// Number of entries that were pinned with [Interned::pin], and are never removed
+    pub pinned: usize,
+}
+
+pub(crate) struct ShardedSet<E = Bytes> {
     pub(crate) shift: usize,
-    pub(crate) hash_builder: ahash::RandomState,
-    pub(crate) shards: Box<[Shard]>,
+    pub(crate) hash_builder: RandomState,
+    pub(crate) shards: Box<[Shard<E>]>,
+    pub(crate) hits: AtomicUsize,
+    pub(crate) inserts: AtomicUsize,
+    pub(crate) pinned: AtomicUsize,
+    pub(crate) generation: AtomicU64,
+    /// Total size of the entries' data
+    pub(crate) bytes: AtomicUsize,
+    pub(crate) memory_limit: usize,
+    pub(crate) max_entry_len: usize,
+    #[cfg(feature = "epoch")]
+    pub(crate) cache: crate::epoch::ReadCache<E>,
 }
 
-impl ShardedSet {
-    fn get_hash_and_shard(&self, value: &[u8]) -> (u64, MutexGuard<'_, LockedShard>) {
+impl<E: Handle> ShardedSet<E> {
+    pub(crate) fn get_hash_and_shard(
+        &self,
+        value: &E::Target,
+    ) -> (u64, ShardGuard<RwLockWriteGuard<'_, LockedShard<E>>>) {
         // hash before locking
         let hash = self.hash_builder.hash_one(value);
+        (hash, self.shard(hash))
+    }
+
+    fn shard_index(&self, hash: u64) -> usize {
         // copied from https://github.com/xacrimon/dashmap/blob/366ce7e7872866a06de66eb95002fa6cf2c117a7/src/lib.rs#L419
-        let idx = ((hash << 7) >> self.shift) as usize;
-        let shard = self.shards[idx].lock();
-        (hash, shard)
+        ((hash << 7) >> self.shift) as usize
     }
 
-    fn hasher(&self, value: &Arc<[u8]>) -> u64 {
-        self.hash_builder.hash_one(value.deref())
+    /// Locks the shard of `hash` exclusively, for inserting or removing entries
+    pub(crate) fn shard(&self, hash: u64) -> ShardGuard<RwLockWriteGuard<'_, LockedShard<E>>> {
+        self.shard_at(self.shard_index(hash))
     }
 
-    pub(crate) fn get_from_existing_ref(&self, value: &[u8]) -> Option<Arc<[u8]>> {
-        let (hash, shard) = self.get_hash_and_shard(value);
-        shard
-            .find(hash, |o| std::ptr::addr_eq(o.as_ptr(), value.as_ptr()))
-            .cloned()
+    /// Locks the shard at `idx` exclusively
+    fn shard_at(&self, idx: usize) -> ShardGuard<RwLockWriteGuard<'_, LockedShard<E>>> {
+        ShardGuard::lock(|| {
+            #[cfg(feature = "tracing")]
+            return crate::tracing::lock_shard(
+                || self.shards[idx].try_write(),
+                || self.shards[idx].write(),
+                idx,
+            );
+            #[cfg(not(feature = "tracing"))]
+            self.shards[idx].write()
+        })
     }
 
-    pub(crate) fn get_or_insert(&self, value: &[u8]) -> Arc<[u8]> {
-        let (hash, mut shard) = self.get_hash_and_shard(value);
+    /// Locks the shard of `hash` for lookups, which don't block each other
+    pub(crate) fn shard_read(&self, hash: u64) -> ShardGuard<RwLockReadGuard<'_, LockedShard<E>>> {
+        let idx = self.shard_index(hash);
+        ShardGuard::lock(|| {
+            #[cfg(feature = "tracing")]
+            return crate::tracing::lock_shard(
+                || self.shards[idx].try_read(),
+                || self.shards[idx].read(),
+                idx,
+            );
+            #[cfg(not(feature = "tracing"))]
+            self.shards[idx].read()
+        })
+    }
 
-        shard
-            .entry(hash, |o| o.deref() == value, |o| self.hasher(o))
-            .or_insert_with(|| Arc::from(value))
-            .get()
-            .clone()
+    pub(crate) fn hasher(&self, value: &E) -> u64 {
+        value.data_hash(&self.hash_builder)
+    }
+
+    /// Looks `value` up without locking its shard, see [crate::epoch]
+    #[cfg(feature = "epoch")]
+    fn get_cached(&self, hash: u64, value: &E::Target) -> Option<E> {
+        match self.cache.get(self.shard_index(hash), hash, value)? {
+            Ok(existing) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(existing)
+            }
+            Err(mut removed) => {
+                self.remove_if_needed(&removed);
+                // the clone may be the last reference to an entry that already left the pool, so
+                // it's dropped the same as an [Interned] is
+                removed.wipe_if_unique();
+                None
+            }
+        }
+    }
+
+    /// Called under the lock of the entry's shard (shared or exclusive) whenever it's returned
+    /// from the pool
+    #[cfg_attr(not(feature = "epoch"), allow(unused_variables))]
+    fn publish(&self, hash: u64, value: &E) {
+        #[cfg(feature = "epoch")]
+        self.cache.publish(self.shard_index(hash), hash, value);
+    }
+
+    /// Called under the lock of the entry's shard right before it's removed, returns false when
+    /// the entry has more than `max_count` references and must stay
+    #[cfg_attr(not(feature = "epoch"), allow(unused_variables))]
+    fn may_remove(&self, hash: u64, value: &E, max_count: usize) -> bool {
+        #[cfg(feature = "epoch")]
+        return self
+            .cache
+            .try_remove(self.shard_index(hash), hash, value, max_count);
+        #[cfg(not(feature = "epoch"))]
+        return value.strong_count() <= max_count;
+    }
+
+    /// `make` is called to construct the new entry from `value` only when it doesn't exist yet
+    ///
+    /// Fails only when inserting the new entry would exceed the memory limit of the pool
+    pub(crate) fn get_or_insert_with<V: Borrow<E::Target>>(
+        &self,
+        value: V,
+        make: impl FnOnce(V, u64) -> E,
+    ) -> Result<E, MemoryLimitExceeded> {
+        // hash before locking
+        let hash = self.hash_builder.hash_one(value.borrow());
+        if let Some(existing) = self.get_with_hash(hash, value.borrow()) {
+            return Ok(existing);
+        }
+
+        // looked up again, in case another thread inserted the value after the shared lock was
+        // released
+        self.get_or_insert_locked(&mut self.shard(hash), hash, value, make)
+    }
+
+    /// Same as [ShardedSet::get_or_insert_with], where `shard` is the locked shard of `hash`
+    fn get_or_insert_locked<V: Borrow<E::Target>>(
+        &self,
+        shard: &mut LockedShard<E>,
+        hash: u64,
+        value: V,
+        make: impl FnOnce(V, u64) -> E,
+    ) -> Result<E, MemoryLimitExceeded> {
+        // the table is only mutated after the new `Arc` is constructed, so a panic while
+        // allocating leaves the shard untouched (room reserved by `entry` is simply left unused)
+        let entry = match shard.entry(hash, |o| o.target() == value.borrow(), |o| self.hasher(o)) {
+            Entry::Occupied(entry) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                entry.get().clone()
+            }
+            Entry::Vacant(entry) => {
+                let size = size_of_val(value.borrow());
+                if !self.has_room_for(size) {
+                    return Err(MemoryLimitExceeded);
+                }
+                self.record_insert(size);
+                entry.insert(make(value, hash)).get().clone()
+            }
+        };
+        self.publish(hash, &entry);
+        Ok(entry)
+    }
+
+    /// Returns the entry holding `value` without inserting it if it doesn't exist
+    pub(crate) fn get(&self, value: &E::Target) -> Option<E> {
+        self.get_with_hash(self.hash_builder.hash_one(value), value)
+    }
+
+    fn get_with_hash(&self, hash: u64, value: &E::Target) -> Option<E> {
+        #[cfg(feature = "epoch")]
+        if let Some(existing) = self.get_cached(hash, value) {
+            return Some(existing);
+        }
+        let shard = self.shard_read(hash);
+
+        let existing = shard.find(hash, |o| o.target() == value).cloned();
+        if let Some(existing) = &existing {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.publish(hash, existing);
+        }
+        existing
+    }
+
+    /// Clones the entries of one shard at a time, so no lock is held while the entries are
+    /// consumed
+    pub(crate) fn iter_snapshot(&self) -> impl Iterator<Item = E> + '_ {
+        self.shards
+            .iter()
+            .flat_map(|o| o.read().iter().cloned().collect::<Vec<_>>())
+    }
+
+    /// Removes the entries that are only referenced by the pool and don't match `f`, returning
+    /// the number of removed entries
+    pub(crate) fn retain(&self, mut f: impl FnMut(&E::Target) -> bool) -> usize {
+        let mut removed = 0;
+        for idx in 0..self.shards.len() {
+            self.shard_at(idx).retain(|o| {
+                // entries that are still referenced must stay, or interning the same data again
+                // would return a different pointer
+                let keep =
+                    o.strong_count() > 1 || f(o.target()) || !self.may_remove(self.hasher(o), o, 1);
+                if !keep {
+                    o.wipe_if_unique();
+                    removed += 1;
+                    self.record_remove(size_of_val(o.target()));
+                }
+                keep
+            });
+        }
+        removed
     }
 
     /// Only try to remove values from the pool when the reference count is two
     /// one for the given [value] and another for the reference in the pool
-    pub(crate) fn remove_if_needed(&self, value: &Arc<[u8]>) {
+    pub(crate) fn remove_if_needed(&self, value: &E) {
         // one count for `value` and one for the entry in our pool
         const MINIMUM_STRONG_COUNT: usize = 2;
 
-        if Arc::strong_count(value) > MINIMUM_STRONG_COUNT {
+        if value.strong_count() > MINIMUM_STRONG_COUNT {
             return;
         }
 
-        let (hash, mut shard) = self.get_hash_and_shard(value);
+        let hash = self.hasher(value);
+        let mut shard = self.shard(hash);
 
-        let Ok(entry) = shard.find_entry(hash, |o| std::ptr::addr_eq(o.as_ptr(), value.as_ptr()))
+        let Ok(entry) = shard.find_entry(hash, |o| std::ptr::addr_eq(o.target(), value.target()))
         else {
             return;
         };
 
         // check again in case the value has been cloned
-        if Arc::strong_count(entry.get()) > MINIMUM_STRONG_COUNT {
+        if !self.may_remove(hash, entry.get(), MINIMUM_STRONG_COUNT) {
             return;
         }
 
         entry.remove();
+        self.record_remove(size_of_val(value.target()));
+    }
+
+    /// Removes the given entries that are only referenced by the pool and by `entries` themselves,
+    /// locking each shard once for all of its entries
+    pub(crate) fn remove_batch(&self, mut entries: Vec<E>) {
+        // one count for the entry in `entries` and one for the entry in our pool
+        const MINIMUM_STRONG_COUNT: usize = 2;
+
+        let key = |o: &E| {
+            let ptr = o.target() as *const E::Target as *const () as usize;
+            (self.shard_index(self.hasher(o)), ptr)
+        };
+        entries.sort_unstable_by_key(key);
+        // the same entry may be queued more than once, which would keep its count too high
+        entries.dedup_by_key(|o| key(o));
+
+        for chunk in entries.chunk_by(|a, b| key(a).0 == key(b).0) {
+            let mut shard = self.shard(self.hasher(&chunk[0]));
+            for value in chunk {
+                if value.strong_count() > MINIMUM_STRONG_COUNT {
+                    continue;
+                }
+                let hash = self.hasher(value);
+                let found =
+                    shard.find_entry(hash, |o| std::ptr::addr_eq(o.target(), value.target()));
+                if let Ok(entry) = found
+                    && self.may_remove(hash, entry.get(), MINIMUM_STRONG_COUNT)
+                {
+                    entry.remove();
+                    self.record_remove(size_of_val(value.target()));
+                }
+            }
+        }
+
+        entries.iter_mut().for_each(E::wipe_if_unique);
+    }
+
+    /// Whether inserting an entry of `size` bytes keeps the pool within its memory limits
+    ///
+    /// Checked under the lock of the entry's shard only, so concurrent insertions to other shards
+    /// may overshoot the limit slightly
+    pub(crate) fn has_room_for(&self, size: usize) -> bool {
+        size <= self.max_entry_len
+            && self.bytes.load(Ordering::Relaxed).saturating_add(size) <= self.memory_limit
+    }
+
+    /// Called for every entry inserted to the pool, where `size` is the size of its data
+    fn record_insert(&self, size: usize) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_insert(size);
+        #[cfg(feature = "tracing")]
+        crate::tracing::record_insert(size);
+    }
+
+    /// Called for every entry removed from the pool, where `size` is the size of its data
+    fn record_remove(&self, size: usize) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_sub(size, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_remove(size);
     }
 
     pub(crate) fn is_empty(&self) -> bool {
@@ -80,18 +521,37 @@ impl ShardedSet {
     }
 
     pub(crate) fn len(&self) -> usize {
-        self.shards.iter().map(|o| o.lock().len()).sum()
+        self.shards.iter().map(|o| o.read().len()).sum()
+    }
+
+    pub(crate) fn try_len(&self) -> Option<usize> {
+        self.shards
+            .iter()
+            .map(|o| o.try_read().map(|o| o.len()))
+            .sum()
+    }
+
+    pub(crate) fn try_shard_memory_usage(&self) -> Vec<Option<MemoryUsage>> {
+        self.shards
+            .iter()
+            .map(|o| {
+                o.try_read().map(|o| MemoryUsage {
+                    len: o.len(),
+                    capacity: o.capacity(),
+                })
+            })
+            .collect()
     }
 
     pub(crate) fn capacity(&self) -> usize {
-        self.shards.iter().map(|o| o.lock().capacity()).sum()
+        self.shards.iter().map(|o| o.read().capacity()).sum()
     }
 
     pub(crate) fn get_memory_usage(&self) -> MemoryUsage {
         self.shards
             .iter()
             .map(|o| {
-                let o = o.lock();
+                let o = o.read();
                 MemoryUsage {
                     len: o.len(),
                     capacity: o.capacity(),
@@ -104,52 +564,1445 @@ impl ShardedSet {
             .unwrap_or_default()
     }
 
+    pub(crate) fn with_capacity_hint(&self, expected_distinct: usize) {
+        let per_shard = capacity_per_shard_for(expected_distinct, self.shards.len());
+
+        for shard in self.shards.iter() {
+            shard.write().reserve(per_shard, |o| self.hasher(o));
+        }
+    }
+
     pub(crate) fn shrink_to_fit(&self) {
         for shard in self.shards.iter() {
-            shard.lock().shrink_to_fit(|o| self.hasher(o));
+            shard.write().shrink_to_fit(|o| self.hasher(o));
+        }
+    }
+
+    /// Identical data always hashes to the same shard, so each shard can be checked on its own
+    pub(crate) fn count_data_duplicates(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|o| {
+                let o = o.read();
+                let mut seen = HashSet::with_capacity(o.len());
+                o.iter().filter(|&o| !seen.insert(o.target())).count()
+            })
+            .sum()
+    }
+
+    /// The hash of every entry is computed again from its data rather than trusting the hash it
+    /// was inserted with, so entries whose data and hash disagree are detected as well
+    pub(crate) fn health_check(&self) -> bool {
+        self.shards.iter().enumerate().all(|(idx, o)| {
+            let o = o.read();
+            o.iter().all(|entry| {
+                let hash = self.hash_builder.hash_one(entry.target());
+                hash == self.hasher(entry)
+                    && self.shard_index(hash) == idx
+                    && o.find(hash, |o| std::ptr::addr_eq(o.target(), entry.target()))
+                        .is_some()
+            })
+        })
+    }
+
+    pub(crate) fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits.load(Ordering::Relaxed),
+            inserts: self.inserts.load(Ordering::Relaxed),
+            pinned: self.pinned.load(Ordering::Relaxed),
         }
     }
+
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.inserts.store(0, Ordering::Relaxed);
+    }
 }
 
-impl Default for ShardedSet {
-    fn default() -> Self {
-        // copied from https://github.com/xacrimon/dashmap/blob/366ce7e7872866a06de66eb95002fa6cf2c117a7/src/lib.rs#L63
-        static DEFAULT_SHARDS_COUNT: LazyLock<usize> = LazyLock::new(|| {
-            (std::thread::available_parallelism().map_or(1, usize::from) * 4).next_power_of_two()
+impl ShardedSet {
+    /// Returns the entry whose data is at `addr`, if it's still in the pool
+    pub(crate) fn get_by_address(&self, hash: u64, addr: usize, len: usize) -> Option<Bytes> {
+        let shard = self.shard_read(hash);
+        shard
+            .find(hash, |o| {
+                o.slice.as_ptr() as usize == addr && o.slice.len() == len
+            })
+            .cloned()
+    }
+
+    pub(crate) fn new_entry(value: &[u8], hash: u64, pool: &'static Pool) -> Bytes {
+        Bytes::from_header_and_slice(Header::new(hash, pool), value)
+    }
+
+    /// Same as [ShardedSet::new_entry], returning an error instead of aborting when the
+    /// allocation fails
+    fn try_new_entry(
+        value: &[u8],
+        hash: u64,
+        pool: &'static Pool,
+    ) -> Result<Bytes, TryReserveError> {
+        let allocated = Bytes::try_from_header_and_slice(Header::new(hash, pool), value);
+        #[cfg(test)]
+        let allocated = allocated.and_then(|o| match FAIL_ENTRY_ALLOCATION.get() {
+            true => Err(triomphe::AllocError),
+            false => Ok(o),
         });
 
+        allocated.map_err(|_| {
+            Layout::from_size_align(ENTRY_OVERHEAD + value.len(), align_of::<usize>())
+                .map_or(TryReserveError::CapacityOverflow, |layout| {
+                    TryReserveError::AllocError { layout }
+                })
+        })
+    }
+
+    pub(crate) fn detailed_stats(&self) -> DetailedStats {
+        let mut stats = DetailedStats {
+            counters: self.stats(),
+            ..Default::default()
+        };
+
+        for shard in self.shards.iter() {
+            let shard = shard.read();
+            stats.shard_entries.push(shard.len());
+            // hashbrown keeps a control byte per bucket next to the buckets themselves
+            stats.heap_bytes += shard.capacity() * (size_of::<Bytes>() + 1);
+            for o in shard.iter() {
+                let len = o.target().len();
+                stats.entries += 1;
+                stats.data_bytes += len;
+                stats.heap_bytes += (ENTRY_OVERHEAD + len).next_multiple_of(align_of::<usize>());
+                stats.largest_entry = stats.largest_entry.max(len);
+            }
+        }
+
+        stats
+    }
+
+    pub(crate) fn get_or_insert(
+        &self,
+        value: &[u8],
+        pool: &'static Pool,
+    ) -> Result<Bytes, MemoryLimitExceeded> {
+        self.get_or_insert_with(value, |o, hash| Self::new_entry(o, hash, pool))
+    }
+
+    /// Same as [ShardedSet::get_or_insert] for many values, hashing them all first and locking each
+    /// shard once for all of its values
+    pub(crate) fn get_or_insert_many(
+        &self,
+        values: &[impl Borrow<[u8]>],
+        pool: &'static Pool,
+    ) -> Vec<Result<Bytes, MemoryLimitExceeded>> {
+        let hashes = values
+            .iter()
+            .map(|o| self.hash_builder.hash_one(o.borrow()))
+            .collect::<Vec<_>>();
+        let order = self.order_by_shard(&hashes);
+
+        let results = order
+            .chunk_by(|&a, &b| self.shard_index(hashes[a]) == self.shard_index(hashes[b]))
+            .flat_map(|o| self.get_or_insert_in_shard(values, &hashes, o, pool));
+        Self::in_original_order(values.len(), results)
+    }
+
+    /// Same as [ShardedSet::get_or_insert_many], where the values are hashed in parallel and the
+    /// shards are processed in parallel, each by a single thread
+    #[cfg(feature = "rayon")]
+    pub(crate) fn par_get_or_insert_many(
+        &self,
+        values: &[impl Borrow<[u8]> + Sync],
+        pool: &'static Pool,
+    ) -> Vec<Result<Bytes, MemoryLimitExceeded>> {
+        use rayon::prelude::*;
+
+        let hashes = values
+            .par_iter()
+            .map(|o| self.hash_builder.hash_one(o.borrow()))
+            .collect::<Vec<_>>();
+        let order = self.order_by_shard(&hashes);
+
+        let results = order
+            .par_chunk_by(|&a, &b| self.shard_index(hashes[a]) == self.shard_index(hashes[b]))
+            .flat_map_iter(|o| self.get_or_insert_in_shard(values, &hashes, o, pool))
+            .collect::<Vec<_>>();
+        Self::in_original_order(values.len(), results)
+    }
+
+    /// The indices of `hashes`, where the indices of each shard are next to each other
+    fn order_by_shard(&self, hashes: &[u64]) -> Vec<usize> {
+        let mut order = (0..hashes.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&i| self.shard_index(hashes[i]));
+        order
+    }
+
+    /// Looks up or inserts the values at `indices`, which all belong to the same shard, locking
+    /// it once
+    fn get_or_insert_in_shard(
+        &self,
+        values: &[impl Borrow<[u8]>],
+        hashes: &[u64],
+        indices: &[usize],
+        pool: &'static Pool,
+    ) -> Vec<(usize, Result<Bytes, MemoryLimitExceeded>)> {
+        let mut shard = self.shard(hashes[indices[0]]);
+        indices
+            .iter()
+            .map(|&i| {
+                let entry = self.get_or_insert_locked(
+                    &mut shard,
+                    hashes[i],
+                    values[i].borrow(),
+                    |o, hash| Self::new_entry(o, hash, pool),
+                );
+                (i, entry)
+            })
+            .collect()
+    }
+
+    fn in_original_order<T>(len: usize, results: impl IntoIterator<Item = (usize, T)>) -> Vec<T> {
+        let mut ordered = Vec::new();
+        ordered.resize_with(len, || None);
+        for (i, o) in results {
+            ordered[i] = Some(o);
+        }
+        ordered
+            .into_iter()
+            .map(|o| o.expect("every value is in one of the shards"))
+            .collect()
+    }
+
+    /// The outer error is returned when the shard fails to grow or the new entry fails to
+    /// allocate, and the inner one when inserting the new entry would exceed the memory limit of
+    /// the pool
+    pub(crate) fn try_get_or_insert(
+        &self,
+        value: &[u8],
+        pool: &'static Pool,
+    ) -> Result<Result<Bytes, MemoryLimitExceeded>, TryReserveError> {
+        let (hash, mut shard) = self.get_hash_and_shard(value);
+
+        if let Some(existing) = shard.find(hash, |o| o.target() == value) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Ok(existing.clone()));
+        }
+
+        if !self.has_room_for(value.len()) {
+            return Ok(Err(MemoryLimitExceeded));
+        }
+
+        shard.try_reserve(1, |o| self.hasher(o))?;
+        let entry = Self::try_new_entry(value, hash, pool)?;
+
+        self.record_insert(value.len());
+        Ok(Ok(shard
+            .insert_unique(hash, entry, |o| self.hasher(o))
+            .get()
+            .clone()))
+    }
+
+    /// Inserts `value` without the lookup before locking its shard, returning the existing entry
+    /// instead when one holds the same data
+    pub(crate) fn insert_unique(&self, value: &[u8], pool: &'static Pool) -> Bytes {
+        let (hash, mut shard) = self.get_hash_and_shard(value);
+
+        // only compares data on a hash match, which never happens when the precondition holds
+        if let Some(existing) = shard.find(hash, |o| o.target() == value) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.publish(hash, existing);
+            return existing.clone();
+        }
+
+        self.record_insert(value.len());
+        shard
+            .insert_unique(hash, Self::new_entry(value, hash, pool), |o| self.hasher(o))
+            .get()
+            .clone()
+    }
+
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&BorrowedInterned)) {
+        for shard in self.shards.iter() {
+            for o in shard.read().iter() {
+                f(BorrowedInterned::new(o.target()));
+            }
+        }
+    }
+
+    pub(crate) fn entries_matching(&self, f: impl Fn(&[u8]) -> bool) -> Vec<(Bytes, usize)> {
+        self.shards
+            .iter()
+            .flat_map(|o| {
+                o.read()
+                    .iter()
+                    .filter(|o| f(o.target()))
+                    .map(|o| {
+                        // not counting the reference held by the pool itself
+                        let count = o.strong_count() - 1;
+                        (o.clone(), count)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+impl<E> ShardedSet<E> {
+    pub(crate) fn with_config(
+        shard_amount: usize,
+        capacity_per_shard: usize,
+        hash_builder: RandomState,
+    ) -> Self {
+        assert!(shard_amount > 1, "shard amount must be larger than one");
+        assert!(
+            shard_amount.is_power_of_two(),
+            "shard amount must be a power of two"
+        );
+
         // copied from https://github.com/xacrimon/dashmap/blob/366ce7e7872866a06de66eb95002fa6cf2c117a7/src/lib.rs#L269
-        let shift =
-            (std::mem::size_of::<usize>() * 8) - DEFAULT_SHARDS_COUNT.trailing_zeros() as usize;
+        let shift = (std::mem::size_of::<usize>() * 8) - shard_amount.trailing_zeros() as usize;
 
         Self {
             shift,
-            hash_builder: Default::default(),
-            shards: (0..*DEFAULT_SHARDS_COUNT)
-                .map(|_| Default::default())
+            hash_builder,
+            shards: (0..shard_amount)
+                .map(|_| RwLock::new(HashTable::with_capacity(capacity_per_shard)))
                 .collect(),
+            hits: Default::default(),
+            inserts: Default::default(),
+            pinned: Default::default(),
+            generation: Default::default(),
+            bytes: Default::default(),
+            memory_limit: usize::MAX,
+            max_entry_len: usize::MAX,
+            #[cfg(feature = "epoch")]
+            cache: crate::epoch::ReadCache::new(
+                shard_amount,
+                crate::epoch::DEFAULT_SLOTS_PER_SHARD,
+            ),
         }
     }
 }
 
-pub(crate) static POOL: LazyLock<ShardedSet> = LazyLock::new(Default::default);
+/// copied from https://github.com/xacrimon/dashmap/blob/366ce7e7872866a06de66eb95002fa6cf2c117a7/src/lib.rs#L63
+/// Returns the capacity each of `shard_amount` shards needs so `expected_distinct` entries in total
+/// fit without growing any of them
+fn capacity_per_shard_for(expected_distinct: usize, shard_amount: usize) -> usize {
+    let per_shard = expected_distinct.div_ceil(shard_amount);
+    // entries aren't spread perfectly evenly between the shards, so leave some headroom of about
+    // four standard deviations
+    per_shard + 4 * per_shard.isqrt()
+}
+
+/// The fewest entries per shard [PoolBuilder::expected_distinct] aims for when it picks the shard
+/// amount, so small pools aren't split into many nearly empty shards
+const MIN_EXPECTED_PER_SHARD: usize = 64;
+
+fn default_shard_amount() -> usize {
+    static DEFAULT_SHARDS_COUNT: LazyLock<usize> = LazyLock::new(|| {
+        (std::thread::available_parallelism().map_or(1, usize::from) * 4).next_power_of_two()
+    });
+    *DEFAULT_SHARDS_COUNT
+}
+
+impl<E> Default for ShardedSet<E> {
+    fn default() -> Self {
+        Self::with_config(default_shard_amount(), 0, Default::default())
+    }
+}
+
+/// Configures a [Pool] before it's created - either the global pool with [configure], or a new
+/// pool with [PoolBuilder::build]
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::pool::PoolBuilder;
+///
+/// let pool = PoolBuilder::new()
+///     .shard_amount(64)
+///     .capacity_per_shard(1024)
+///     .build();
+///
+/// assert!(pool.capacity() >= 64 * 1024);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PoolBuilder {
+    shard_amount: Option<usize>,
+    capacity_per_shard: usize,
+    expected_distinct: usize,
+    hash_builder: RandomState,
+    memory_limit: usize,
+    max_entry_len: usize,
+    on_overflow: OverflowBehavior,
+    sweep_threshold: usize,
+    ascii_case_insensitive: bool,
+    canonicalize: Option<Canonicalizer>,
+    #[cfg(feature = "epoch")]
+    read_cache_slots: usize,
+}
+
+/// Returns the form of a value that is kept in the pool, see [PoolBuilder::canonicalize]
+pub type Canonicalizer = fn(&[u8]) -> Cow<'_, [u8]>;
+
+impl Default for PoolBuilder {
+    fn default() -> Self {
+        Self {
+            shard_amount: None,
+            capacity_per_shard: 0,
+            expected_distinct: 0,
+            hash_builder: Default::default(),
+            memory_limit: usize::MAX,
+            max_entry_len: usize::MAX,
+            on_overflow: Default::default(),
+            sweep_threshold: 0,
+            ascii_case_insensitive: false,
+            canonicalize: None,
+            #[cfg(feature = "epoch")]
+            read_cache_slots: crate::epoch::DEFAULT_SLOTS_PER_SHARD,
+        }
+    }
+}
+
+impl PoolBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the number of shards, which defaults to four times the
+    /// [available_parallelism](std::thread::available_parallelism) rounded up to a power of two
+    ///
+    /// # Panics
+    ///
+    /// [PoolBuilder::build] panics unless `shard_amount` is a power of two larger than one
+    pub fn shard_amount(mut self, shard_amount: usize) -> Self {
+        self.shard_amount = Some(shard_amount);
+        self
+    }
+
+    /// Sets the number of entries each shard has room for before it needs to grow
+    pub fn capacity_per_shard(mut self, capacity_per_shard: usize) -> Self {
+        self.capacity_per_shard = capacity_per_shard;
+        self
+    }
+
+    /// Sizes the pool for `expected_distinct` entries in total, so interning up to that many
+    /// distinct values never grows the shards
+    ///
+    /// Unless [PoolBuilder::shard_amount] is set, the shard amount is lowered from its default so
+    /// each shard expects at least 64 entries - the capacity is then split evenly between the
+    /// shards, with the same headroom as [with_capacity_hint], and [PoolBuilder::capacity_per_shard]
+    /// is only used if it's larger
+    ///
+    /// Unlike [with_capacity_hint], which grows the shards of the existing global pool (rehashing the
+    /// entries it already holds), the shards are allocated at this size when the pool is built -
+    /// calling [with_capacity_hint] later reserves room on top of the entries interned by then
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::pool::PoolBuilder;
+    ///
+    /// let pool = PoolBuilder::new().expected_distinct(100_000).build();
+    ///
+    /// assert!(pool.capacity() >= 100_000);
+    /// ```
+    pub fn expected_distinct(mut self, expected_distinct: usize) -> Self {
+        self.expected_distinct = expected_distinct;
+        self
+    }
+
+    /// Sets the hash builder used to hash the data of the entries, e.g. to use fixed seeds
+    pub fn hash_builder(mut self, hash_builder: RandomState) -> Self {
+        self.hash_builder = hash_builder;
+        self
+    }
+
+    /// Limits the total size of the entries' data, which is unlimited by default
+    ///
+    /// Interning values that don't fit in the pool is handled according to
+    /// [PoolBuilder::on_overflow], or fails with [Interned::try_new_limited]
+    ///
+    /// Meant to bound the memory used for interning untrusted data
+    pub fn memory_limit(mut self, memory_limit: usize) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Limits the length of a single entry's data, which is unlimited by default
+    ///
+    /// Longer values are handled the same as values that don't fit in [PoolBuilder::memory_limit],
+    /// so one huge value (such as a runaway command line) is never kept in the pool
+    pub fn max_entry_len(mut self, max_entry_len: usize) -> Self {
+        self.max_entry_len = max_entry_len;
+        self
+    }
+
+    /// Sets how [Interned::new] handles values that don't fit in the pool's memory limits
+    pub fn on_overflow(mut self, on_overflow: OverflowBehavior) -> Self {
+        self.on_overflow = on_overflow;
+        self
+    }
+
+    /// Defers the removal of entries whose last [Interned] is dropped, instead of locking their
+    /// shard on every drop
+    ///
+    /// Such entries are queued, and once `sweep_threshold` of them are queued (or when
+    /// [Pool::sweep] is called) they are removed in one batch, locking each shard once - which
+    /// speeds up the teardown of large collections
+    ///
+    /// Queued entries are still in the pool, so interning their data again before they are
+    /// removed reuses them
+    pub fn deferred_removal(mut self, sweep_threshold: usize) -> Self {
+        self.sweep_threshold = sweep_threshold;
+        self
+    }
+
+    /// Folds ASCII letters to lowercase before interning, so values that only differ in the case
+    /// of ASCII letters (such as `HOST` and `host`) share the same entry, which holds the
+    /// lowercase form
+    ///
+    /// Meant for pools of case-insensitive values, such as HTTP header names or Windows paths
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::{Interned, pool::PoolBuilder};
+    ///
+    /// let pool = Box::leak(Box::new(PoolBuilder::new().ascii_case_insensitive(true).build()));
+    ///
+    /// let a = Interned::new_in(pool, b"HOST");
+    /// let b = Interned::new_in(pool, b"host");
+    ///
+    /// assert_eq!(a.as_ptr(), b.as_ptr());
+    /// assert_eq!(&a[..], b"host");
+    /// ```
+    pub fn ascii_case_insensitive(mut self, ascii_case_insensitive: bool) -> Self {
+        self.ascii_case_insensitive = ascii_case_insensitive;
+        self
+    }
+
+    /// Normalizes values with `canonicalize` before interning them, so values with the same
+    /// canonical form share the same entry, which holds the canonical form
+    ///
+    /// Centralizes normalizations such as trimming whitespace, stripping trailing slashes or
+    /// Unicode normalization in the pool, so all call sites agree on them - `canonicalize` must
+    /// return the same value when given a canonical value, and is applied before
+    /// [PoolBuilder::ascii_case_insensitive]
+    ///
+    /// Strings interned as [InternedStr] whose canonical form isn't valid UTF-8 are interned as
+    /// given instead
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use intern_mint::{Interned, pool::PoolBuilder};
+    ///
+    /// fn trim(value: &[u8]) -> Cow<'_, [u8]> {
+    ///     Cow::Borrowed(value.trim_ascii())
+    /// }
+    ///
+    /// let pool = Box::leak(Box::new(PoolBuilder::new().canonicalize(trim).build()));
+    ///
+    /// let a = Interned::new_in(pool, b" value\n");
+    ///
+    /// assert_eq!(&a[..], b"value");
+    /// assert_eq!(a.as_ptr(), Interned::new_in(pool, b"value").as_ptr());
+    /// ```
+    ///
+    /// [InternedStr]: crate::InternedStr
+    pub fn canonicalize(mut self, canonicalize: Canonicalizer) -> Self {
+        self.canonicalize = Some(canonicalize);
+        self
+    }
+
+    /// Sets the number of entries each shard caches for lookups that don't lock the shard, which
+    /// defaults to 256
+    ///
+    /// Meant for workloads that intern a large set of hot values, where entries sharing a slot
+    /// keep replacing each other in the cache
+    ///
+    /// # Panics
+    ///
+    /// [PoolBuilder::build] panics if `read_cache_slots` is zero
+    #[cfg(feature = "epoch")]
+    pub fn read_cache_slots(mut self, read_cache_slots: usize) -> Self {
+        self.read_cache_slots = read_cache_slots;
+        self
+    }
+
+    pub fn build(self) -> Pool {
+        let shard_amount = self.shard_amount.unwrap_or_else(|| {
+            let fitting = (self.expected_distinct / MIN_EXPECTED_PER_SHARD).next_power_of_two();
+            match self.expected_distinct {
+                0 => default_shard_amount(),
+                _ => default_shard_amount().min(fitting).max(2),
+            }
+        });
+        let capacity_per_shard = self
+            .capacity_per_shard
+            .max(capacity_per_shard_for(self.expected_distinct, shard_amount));
+
+        let mut set = ShardedSet::with_config(shard_amount, capacity_per_shard, self.hash_builder);
+        set.memory_limit = self.memory_limit;
+        set.max_entry_len = self.max_entry_len;
+        #[cfg(feature = "epoch")]
+        {
+            set.cache = crate::epoch::ReadCache::new(shard_amount, self.read_cache_slots);
+        }
+
+        Pool {
+            set,
+            on_overflow: self.on_overflow,
+            sweep_threshold: self.sweep_threshold,
+            pending: Default::default(),
+            ascii_case_insensitive: self.ascii_case_insensitive,
+            canonicalize: self.canonicalize,
+        }
+    }
+}
+
+/// How values that don't fit in the memory limit of a pool are interned, see
+/// [PoolBuilder::memory_limit]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowBehavior {
+    /// The value is returned as an [Interned] that is not kept in the pool, so it doesn't share a
+    /// pointer with other values holding the same data
+    #[default]
+    Unpooled,
+    /// Entries that are only referenced by the pool are removed first (see [clear_unreferenced]),
+    /// and the value is returned unpooled if it still doesn't fit
+    Evict,
+}
+
+/// Returned by [Interned::try_new_limited] when the value doesn't fit in the memory limits of the
+/// pool - either [PoolBuilder::memory_limit] or [PoolBuilder::max_entry_len]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryLimitExceeded;
+
+impl Display for MemoryLimitExceeded {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("the memory limit of the pool has been exceeded")
+    }
+}
+
+impl Error for MemoryLimitExceeded {}
+
+/// Returned by [configure] when the global pool has already been created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyInitialized;
+
+impl Display for AlreadyInitialized {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("the global pool has already been initialized")
+    }
+}
+
+impl Error for AlreadyInitialized {}
+
+/// A pool of interned slices
+///
+/// [Interned::new] interns into a global pool, while [Interned::new_in] interns into a given pool -
+/// allowing to isolate workloads (such as tenants or subsystems) from each other, and to inspect
+/// the memory usage of each of them separately
+///
+/// Every [Interned] keeps a reference to the pool it came from, so pools must be `'static` - either
+/// kept in a `static` or leaked once with [Box::leak]
+///
+/// Note that values interned in different pools never share a pointer, so they are not equal even
+/// when holding the same data
+///
+/// # Example
+///
+/// ```
+/// use std::sync::LazyLock;
+///
+/// use intern_mint::{Interned, pool::Pool};
+///
+/// static TENANT_POOL: LazyLock<Pool> = LazyLock::new(Pool::new);
+///
+/// let a = Interned::new_in(&TENANT_POOL, b"hello");
+/// let b = Interned::new_in(&TENANT_POOL, b"hello");
+///
+/// assert_eq!(a.as_ptr(), b.as_ptr());
+/// assert_ne!(a.as_ptr(), Interned::new(b"hello").as_ptr());
+/// assert_eq!(TENANT_POOL.len(), 1);
+/// ```
+#[derive(Default)]
+pub struct Pool {
+    pub(crate) set: ShardedSet,
+    on_overflow: OverflowBehavior,
+    /// Zero unless removals are deferred, see [PoolBuilder::deferred_removal]
+    sweep_threshold: usize,
+    /// Entries whose last [Interned] was dropped, waiting to be removed
+    pending: Mutex<Vec<Bytes>>,
+    /// See [PoolBuilder::ascii_case_insensitive]
+    ascii_case_insensitive: bool,
+    /// See [PoolBuilder::canonicalize]
+    canonicalize: Option<Canonicalizer>,
+}
+
+impl Pool {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn builder() -> PoolBuilder {
+        PoolBuilder::new()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.set.capacity()
+    }
+
+    pub fn get_memory_usage(&self) -> MemoryUsage {
+        self.set.get_memory_usage()
+    }
+
+    pub fn shrink_to_fit(&self) {
+        self.set.shrink_to_fit();
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.set.stats()
+    }
+
+    /// Same as [detailed_stats], for the entries of this pool
+    pub fn detailed_stats(&self) -> DetailedStats {
+        self.set.detailed_stats()
+    }
+
+    /// Same as [retain], for the entries of this pool
+    pub fn retain(&self, f: impl FnMut(&[u8]) -> bool) -> usize {
+        self.set.retain(f)
+    }
+
+    /// Same as [clear_unreferenced], for the entries of this pool
+    pub fn clear_unreferenced(&self) -> usize {
+        self.set.retain(|_| false)
+    }
+
+    /// Same as [iter_snapshot], for the entries of this pool
+    pub fn iter_snapshot(&self) -> impl Iterator<Item = Interned> + '_ {
+        self.set.iter_snapshot().map(Interned::from_existing)
+    }
+
+    /// The form of `value` that is kept in the pool, see [PoolBuilder::canonicalize] and
+    /// [PoolBuilder::ascii_case_insensitive]
+    pub(crate) fn canonical<'a>(&self, value: &'a [u8]) -> Cow<'a, [u8]> {
+        let value = match self.canonicalize {
+            Some(canonicalize) => canonicalize(value),
+            None => Cow::Borrowed(value),
+        };
+        if self.ascii_case_insensitive && value.iter().any(u8::is_ascii_uppercase) {
+            return Cow::Owned(value.to_ascii_lowercase());
+        }
+        value
+    }
+
+    /// Interns `value`, handling values that don't fit in the memory limit according to the
+    /// pool's [OverflowBehavior]
+    pub(crate) fn intern(&'static self, value: &[u8]) -> Interned {
+        self.intern_canonical_or_unpooled(&self.canonical(value))
+    }
+
+    /// Same as [Pool::intern] for a value that must stay valid UTF-8, see [InternedStr]
+    ///
+    /// [PoolBuilder::ascii_case_insensitive] keeps UTF-8 valid, but [PoolBuilder::canonicalize]
+    /// may not - when the canonical form isn't valid UTF-8, `value` is interned as is instead
+    ///
+    /// [InternedStr]: crate::InternedStr
+    pub(crate) fn intern_str(&'static self, value: &str) -> Interned {
+        let canonical = self.canonical(value.as_bytes());
+        if std::str::from_utf8(&canonical).is_ok() {
+            return self.intern_canonical_or_unpooled(&canonical);
+        }
+        self.intern_canonical_or_unpooled(value.as_bytes())
+    }
+
+    /// Same as [Pool::intern] without canonicalizing `value`, for data that must be kept exactly
+    /// as given (such as the compressed data of [CompressedInterned])
+    ///
+    /// [CompressedInterned]: crate::CompressedInterned
+    #[cfg(feature = "lz4")]
+    pub(crate) fn intern_raw(&'static self, value: &[u8]) -> Interned {
+        self.intern_canonical_or_unpooled(value)
+    }
+
+    /// Same as [Pool::intern], where `value` is already in its canonical form
+    fn intern_canonical_or_unpooled(&'static self, value: &[u8]) -> Interned {
+        self.intern_canonical(value)
+            .unwrap_or_else(|_| Interned::from_existing(self.unpooled(value)))
+    }
+
+    /// Same as [Pool::intern], but fails instead of returning an unpooled value
+    pub(crate) fn intern_limited(
+        &'static self,
+        value: &[u8],
+    ) -> Result<Interned, MemoryLimitExceeded> {
+        self.intern_canonical(&self.canonical(value))
+    }
+
+    /// Returns the entry holding `value` without inserting it if it doesn't exist
+    pub(crate) fn get(&self, value: &[u8]) -> Option<Interned> {
+        self.set
+            .get(&self.canonical(value))
+            .map(Interned::from_existing)
+    }
+
+    /// Same as [Pool::intern_limited], where `value` is already in its canonical form
+    fn intern_canonical(&'static self, value: &[u8]) -> Result<Interned, MemoryLimitExceeded> {
+        self.set
+            .get_or_insert(value, self)
+            .or_else(|o| match self.on_overflow {
+                OverflowBehavior::Unpooled => Err(o),
+                OverflowBehavior::Evict => {
+                    self.clear_unreferenced();
+                    self.set.get_or_insert(value, self)
+                }
+            })
+            .map(Interned::from_existing)
+    }
+
+    /// Same as [Pool::intern] for many values, see [intern_many]
+    pub(crate) fn intern_many(&'static self, values: &[impl Borrow<[u8]>]) -> Vec<Interned> {
+        let values = values
+            .iter()
+            .map(|o| self.canonical(o.borrow()))
+            .collect::<Vec<_>>();
+        self.collect_results(&values, self.set.get_or_insert_many(&values, self))
+    }
+
+    /// Same as [Pool::intern_many], interning in parallel, see [par_intern]
+    #[cfg(feature = "rayon")]
+    pub(crate) fn par_intern_many(
+        &'static self,
+        values: &[impl Borrow<[u8]> + Sync],
+    ) -> Vec<Interned> {
+        use rayon::prelude::*;
+
+        let values = values
+            .par_iter()
+            .map(|o| self.canonical(o.borrow()))
+            .collect::<Vec<_>>();
+        self.collect_results(&values, self.set.par_get_or_insert_many(&values, self))
+    }
+
+    fn collect_results(
+        &'static self,
+        values: &[impl Borrow<[u8]>],
+        results: Vec<Result<Bytes, MemoryLimitExceeded>>,
+    ) -> Vec<Interned> {
+        results
+            .into_iter()
+            .zip(values)
+            .map(|(o, value)| match o {
+                Ok(o) => Interned::from_existing(o),
+                // handled one by one, according to the pool's overflow behavior
+                Err(MemoryLimitExceeded) => self.intern(value.borrow()),
+            })
+            .collect()
+    }
+
+    /// Called when an [Interned] of this pool is dropped
+    pub(crate) fn release(&self, value: &Bytes) {
+        if self.sweep_threshold == 0 {
+            return self.set.remove_if_needed(value);
+        }
+
+        // one count for `value` and one for the entry in our pool
+        if value.strong_count() > 2 {
+            return;
+        }
+
+        // `value` itself is still referenced until it's dropped, so it's queued for the next batch
+        let batch = {
+            let mut pending = self.pending.lock();
+            let batch =
+                (pending.len() >= self.sweep_threshold).then(|| std::mem::take(&mut *pending));
+            pending.push(value.clone());
+            batch
+        };
+        if let Some(batch) = batch {
+            self.set.remove_batch(batch);
+        }
+    }
+
+    /// Removes the entries queued for removal, see [PoolBuilder::deferred_removal]
+    pub fn sweep(&self) {
+        let batch = std::mem::take(&mut *self.pending.lock());
+        self.set.remove_batch(batch);
+    }
+
+    /// Constructs an entry that is not kept in the pool
+    pub(crate) fn unpooled(&'static self, value: &[u8]) -> Bytes {
+        ShardedSet::new_entry(value, self.set.hash_builder.hash_one(value), self)
+    }
+
+    /// Returns the entry whose data is at `addr`, if it's still in this pool, see [WeakInterned]
+    ///
+    /// [WeakInterned]: crate::WeakInterned
+    pub(crate) fn get_by_address(&self, hash: u64, addr: usize, len: usize) -> Option<Interned> {
+        self.set
+            .get_by_address(hash, addr, len)
+            .map(Interned::from_existing)
+    }
+}
+
+pub(crate) static POOL: LazyLock<Pool> = LazyLock::new(|| {
+    let mut config = CONFIG.lock();
+    std::mem::replace(&mut *config, Config::Initialized)
+        .into_builder()
+        .build()
+});
+
+enum Config {
+    Pending(Option<PoolBuilder>),
+    Initialized,
+}
+
+impl Config {
+    fn into_builder(self) -> PoolBuilder {
+        match self {
+            Self::Pending(builder) => builder.unwrap_or_default(),
+            Self::Initialized => unreachable!("the global pool is only initialized once"),
+        }
+    }
+}
+
+/// Both [configure] and the initialization of the global pool lock it, so a configuration is
+/// either applied or rejected, and never silently lost
+static CONFIG: Mutex<Config> = Mutex::new(Config::Pending(None));
+
+/// Configures the global pool, which is created on first use
+///
+/// Meant to be called once on startup, before any value is interned - returns [AlreadyInitialized]
+/// if the global pool has already been created, in which case the configuration is not applied
+///
+/// Calling it again before the global pool is created replaces the previous configuration
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// pool::configure(pool::PoolBuilder::new().shard_amount(128)).unwrap();
+///
+/// let _a = Interned::new(b"hello");
+///
+/// assert!(pool::configure(pool::PoolBuilder::new()).is_err());
+/// ```
+pub fn configure(builder: PoolBuilder) -> Result<(), AlreadyInitialized> {
+    match &mut *CONFIG.lock() {
+        Config::Pending(config) => {
+            *config = Some(builder);
+            Ok(())
+        }
+        Config::Initialized => Err(AlreadyInitialized),
+    }
+}
+
+/// Removes the entries queued for removal in the global pool, when it's configured with
+/// [PoolBuilder::deferred_removal]
+pub fn sweep() {
+    POOL.sweep();
+}
 
 pub fn is_empty() -> bool {
-    POOL.is_empty()
+    POOL.set.is_empty()
 }
 
 pub fn len() -> usize {
-    POOL.len()
+    POOL.set.len()
+}
+
+/// Same as [len], but returns [None] instead of blocking if any of the shards is locked
+pub fn try_len() -> Option<usize> {
+    POOL.set.try_len()
+}
+
+/// Returns the [MemoryUsage] of every shard in the pool, or [None] for the shards that are locked
+/// instead of blocking on them
+pub fn try_shard_memory_usage() -> Vec<Option<MemoryUsage>> {
+    POOL.set.try_shard_memory_usage()
 }
 
 pub fn capacity() -> usize {
-    POOL.capacity()
+    POOL.set.capacity()
 }
 
 pub fn get_memory_usage() -> MemoryUsage {
-    POOL.get_memory_usage()
+    POOL.set.get_memory_usage()
+}
+
+/// Reserves room in the pool's shards for `expected_distinct` entries in total, so interning up to
+/// that many distinct values won't need to grow the shards
+///
+/// The room is reserved on top of the entries that already exist in the pool, and is split between
+/// the shards, with some headroom since entries aren't spread perfectly evenly between them
+///
+/// Meant to be called once on startup, when the approximate number of distinct values is known -
+/// [PoolBuilder::expected_distinct] given to [configure] sizes the shards when they are created
+/// instead, and also picks the shard amount
+pub fn with_capacity_hint(expected_distinct: usize) {
+    POOL.set.with_capacity_hint(expected_distinct);
 }
 
 pub fn shrink_to_fit() {
-    POOL.shrink_to_fit();
+    POOL.set.shrink_to_fit();
+}
+
+/// Interns values that are known to be distinct, locking each value's shard once and skipping the
+/// lookup that runs before locking it
+///
+/// # Precondition
+///
+/// The values should be distinct from each other, and should not already exist in the pool
+///
+/// Breaking the precondition is detected by a probe under the shard's lock, which only compares
+/// the data of entries of the same hash - the existing entry is returned, the same as
+/// [Interned::new], so no duplicate is ever inserted
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let values = pool::intern_all_unique([b"first unique".to_vec(), b"second unique".to_vec()]);
+///
+/// assert_eq!(values[0].as_ptr(), Interned::new(b"first unique").as_ptr());
+/// ```
+pub fn intern_all_unique(values: impl IntoIterator<Item = Vec<u8>>) -> Vec<Interned> {
+    values
+        .into_iter()
+        .map(|o| {
+            let o = POOL.canonical(&o);
+            if POOL.set.has_room_for(o.len()) {
+                Interned::from_existing(POOL.set.insert_unique(&o, &POOL))
+            } else {
+                Interned::new(&o)
+            }
+        })
+        .collect()
+}
+
+/// Interns `values` in the global pool, returning them in the same order
+///
+/// All the values are hashed first and then grouped by their shard, so each shard is locked once
+/// for the whole batch rather than once per value
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let values = pool::intern_many(&[b"a".as_slice(), b"b", b"a"]);
+///
+/// assert_eq!(values[0].as_ptr(), values[2].as_ptr());
+/// assert_eq!(values[1].as_ptr(), Interned::new(b"b").as_ptr());
+/// ```
+pub fn intern_many(values: &[&[u8]]) -> Vec<Interned> {
+    POOL.intern_many(values)
+}
+
+/// Interns the NFC normalized form of `value`, so canonically equivalent strings share the same
+/// pointer
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::pool;
+///
+/// let composed = pool::intern_nfc("\u{e9}");
+/// let decomposed = pool::intern_nfc("e\u{301}");
+///
+/// assert_eq!(composed.as_ptr(), decomposed.as_ptr());
+/// ```
+#[cfg(feature = "unicode-norm")]
+pub fn intern_nfc(value: &str) -> Interned {
+    crate::unicode_norm::nfc(value)
+}
+
+/// Interns the concatenation of `parts` without allocating when the value already exists in the
+/// pool
+///
+/// Small values are concatenated into a reusable per-thread buffer, so only a miss allocates (the
+/// new entry itself), larger values are concatenated into a temporary buffer
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let interned = pool::intern_from_parts(&[b"ab", b"cd"]);
+///
+/// assert_eq!(interned.as_ptr(), Interned::from("abcd").as_ptr());
+/// ```
+pub fn intern_from_parts(parts: &[&[u8]]) -> Interned {
+    let len = parts.iter().map(|o| o.len()).sum::<usize>();
+    if len > MAX_BUFFERED_LEN {
+        return parts.concat().into();
+    }
+
+    with_buffer(|buffer| {
+        for part in parts {
+            buffer.extend_from_slice(part);
+        }
+    })
+}
+
+/// Bigger values are not kept around in the per-thread buffers
+const MAX_BUFFERED_LEN: usize = 4096;
+
+/// Interns the value written by `write` into an empty per-thread buffer
+pub(crate) fn with_buffer(write: impl FnOnce(&mut Vec<u8>)) -> Interned {
+    with_buffer_into(write, Interned::new)
+}
+
+/// Same as [with_buffer], where the written value is interned by `intern`
+pub(crate) fn with_buffer_into(
+    write: impl FnOnce(&mut Vec<u8>),
+    intern: impl FnOnce(&[u8]) -> Interned,
+) -> Interned {
+    thread_local! {
+        static BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // taken out of the cell, so `write` may intern into a buffer of its own (e.g. when formatting
+    // a value calls [format_interned] again)
+    let mut buffer = BUFFER.take();
+    buffer.clear();
+    write(&mut buffer);
+    let interned = intern(&buffer);
+    if buffer.capacity() <= MAX_BUFFERED_LEN {
+        BUFFER.set(buffer);
+    }
+    interned
+}
+
+/// Interns the formatted `args`, formatting into a reusable per-thread buffer instead of a
+/// temporary [String]
+///
+/// Used by [format_interned], which is usually more convenient
+///
+/// [format_interned]: crate::format_interned
+pub fn intern_fmt(args: fmt::Arguments) -> Interned {
+    if let Some(value) = args.as_str() {
+        return Interned::new(value.as_bytes());
+    }
+
+    with_buffer(|buffer| {
+        std::io::Write::write_fmt(buffer, args).expect("formatting into a vector never fails")
+    })
+}
+
+thread_local! {
+    static THREAD_CACHE: RefCell<VecDeque<Interned>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Interns `value` in the global pool, looking it up in a small per-thread cache of the values
+/// that were interned last by this function first
+///
+/// Values found in the cache are returned without hashing them or locking the pool, which suits
+/// hot paths that intern the same handful of values over and over again - values are looked up by
+/// their canonical form (see [PoolBuilder::canonicalize]), so this returns the same entry as
+/// [Interned::new]
+///
+/// Note that the cache keeps the last 64 values alive until they are evicted from it, until
+/// [clear_thread_cache] is called or until the thread exits
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let a = pool::intern_cached(b"hot");
+/// let b = pool::intern_cached(b"hot");
+///
+/// assert_eq!(a.as_ptr(), b.as_ptr());
+/// assert_eq!(a.as_ptr(), Interned::new(b"hot").as_ptr());
+/// ```
+pub fn intern_cached(value: &[u8]) -> Interned {
+    const CAPACITY: usize = 64;
+
+    // the cache holds entries, which hold canonical forms, so it must be looked up by the canonical
+    // form of `value` to find the entry interning it would return
+    let value = POOL.canonical(value);
+
+    THREAD_CACHE.with_borrow_mut(|cache| {
+        // the most recently used values are kept at the front
+        if let Some(index) = cache.iter().position(|o| o[..] == value[..]) {
+            let interned = cache.remove(index).expect("index was just found");
+            cache.push_front(interned.clone());
+            return interned;
+        }
+
+        let interned = POOL.intern_canonical_or_unpooled(&value);
+        if cache.len() == CAPACITY {
+            cache.pop_back();
+        }
+        cache.push_front(interned.clone());
+        interned
+    })
+}
+
+/// Drops the values cached by [intern_cached] on the current thread, so they can be removed from
+/// the pool
+pub fn clear_thread_cache() {
+    // taken out first, so dropping the values doesn't happen while the cache is borrowed
+    drop(THREAD_CACHE.take());
+}
+
+/// Interns `values` in parallel, returning them in the same order
+///
+/// The values are hashed in parallel and grouped by their shard, and each shard is then locked
+/// once by a single thread for all of its values - so threads don't contend on the same shard
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let values = pool::par_intern(vec![b"a".to_vec(), b"b".to_vec(), b"a".to_vec()]);
+///
+/// assert_eq!(values[0].as_ptr(), values[2].as_ptr());
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_intern(values: impl rayon::iter::IntoParallelIterator<Item = Vec<u8>>) -> Vec<Interned> {
+    use rayon::iter::ParallelIterator;
+
+    POOL.par_intern_many(&values.into_par_iter().collect::<Vec<_>>())
+}
+
+/// Builds a [HashMap] keyed by [Interned] from `(key, value)` pairs, interning the keys on the way
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{BorrowedInterned, Interned, pool};
+///
+/// let map = pool::map_from_iter([(b"a".as_slice(), 1), (b"b", 2)]);
+///
+/// let key = Interned::new(b"a");
+/// let borrowed_key: &BorrowedInterned = &key;
+/// assert_eq!(map.get(borrowed_key), Some(&1));
+/// ```
+pub fn map_from_iter<'a, V>(iter: impl IntoIterator<Item = (&'a [u8], V)>) -> HashMap<Interned, V> {
+    iter.into_iter()
+        .map(|(key, value)| (Interned::new(key), value))
+        .collect()
+}
+
+/// Removes the values whose data is identical to a previous value, keeping the first one
+///
+/// As opposed to the default pointer-based equality of [Interned], this compares the actual data,
+/// so it can be used as a safety net for collections that may hold duplicated entries (see
+/// [count_data_duplicates])
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let values = pool::dedup_by_data([
+///     Interned::new(b"a"),
+///     Interned::new(b"b"),
+///     Interned::new(b"a"),
+/// ]);
+///
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(&values[0][..], b"a");
+/// assert_eq!(&values[1][..], b"b");
+/// ```
+pub fn dedup_by_data(values: impl IntoIterator<Item = Interned>) -> Vec<Interned> {
+    let mut values = values.into_iter().collect::<Vec<_>>();
+
+    let mut seen = HashSet::with_capacity(values.len());
+    let mut keep = values
+        .iter()
+        .map(|o| seen.insert(&o[..]))
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    values.retain(|_| keep.next().unwrap_or_default());
+    values
+}
+
+/// Calls `f` with a borrowed view of every entry in the pool, without cloning any of them
+///
+/// The shards are locked one at a time, and the currently visited shard stays locked while `f`
+/// runs - so `f` must not construct or drop any [Interned], as that may deadlock on the same shard
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let _a = Interned::new(b"hello");
+///
+/// let mut total_len = 0;
+/// pool::for_each(|o| total_len += o.len());
+///
+/// assert!(total_len >= 5);
+/// ```
+pub fn for_each(f: impl FnMut(&BorrowedInterned)) {
+    POOL.set.for_each(f);
+}
+
+/// Returns handles to all the entries in the pool
+///
+/// Each shard is locked only while its entries are cloned, so the handles may be consumed (and
+/// dropped) freely - but entries inserted or removed while iterating may or may not be returned
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let a = Interned::new(b"process name");
+///
+/// assert!(pool::iter_snapshot().any(|o| o.as_ptr() == a.as_ptr()));
+/// ```
+pub fn iter_snapshot() -> impl Iterator<Item = Interned> {
+    POOL.iter_snapshot()
+}
+
+/// Removes the entries that don't match `f`, returning the number of removed entries
+///
+/// Entries that are still referenced by any [Interned] (including pinned entries) are kept no
+/// matter what `f` returns, so this only reclaims the memory of entries that are already unused
+///
+/// Note that every shard is locked in turn while `f` runs - so `f` must not construct or drop any
+/// [Interned], as that may deadlock on the same shard
+pub fn retain(f: impl FnMut(&[u8]) -> bool) -> usize {
+    POOL.retain(f)
+}
+
+/// Removes the entries that are only referenced by the pool itself, returning the number of
+/// removed entries
+///
+/// Entries are normally removed as soon as their last [Interned] is dropped, but concurrent drops
+/// may race and leave an unreferenced entry behind - this is meant to be called periodically by
+/// long-running processes to reclaim their memory
+pub fn clear_unreferenced() -> usize {
+    POOL.clear_unreferenced()
+}
+
+/// Counts the occurrences of every byte value across all the entries in the pool, where each entry
+/// is counted once no matter how many references to it exist
+///
+/// Note that every shard is locked in turn, so this is meant for diagnostics and not for hot paths
+pub fn byte_histogram() -> [u64; 256] {
+    let mut histogram = [0; 256];
+    for_each(|o| {
+        for &byte in o.iter() {
+            histogram[byte as usize] += 1;
+        }
+    });
+    histogram
+}
+
+/// Returns handles to all the entries whose data matches `f`, alongside the number of references
+/// to each entry at the time it was visited
+///
+/// The reference held by the pool itself and the returned handles are not counted
+///
+/// Note that every shard is locked in turn, so this is meant for diagnostics and not for hot paths
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let a = Interned::new(b"needle in a haystack");
+/// let b = a.clone();
+///
+/// let entries = pool::entries_matching(|o| o.starts_with(b"needle"));
+///
+/// assert_eq!(entries.len(), 1);
+/// assert_eq!(entries[0].0.as_ptr(), a.as_ptr());
+/// assert_eq!(entries[0].1, 2);
+/// ```
+pub fn entries_matching(f: impl Fn(&[u8]) -> bool) -> Vec<(Interned, usize)> {
+    POOL.set
+        .entries_matching(f)
+        .into_iter()
+        .map(|(o, count)| (Interned::from_existing(o), count))
+        .collect()
+}
+
+/// Counts the entries whose data is identical to another entry in the pool
+///
+/// This should always return zero - a non-zero count means that pointer equality can no longer be
+/// trusted for the duplicated values
+///
+/// Note that every shard is locked in turn, so this is meant for diagnostics and not for hot paths
+pub fn count_data_duplicates() -> usize {
+    POOL.set.count_data_duplicates()
+}
+
+/// Verifies that every entry in the pool can be found by the hash of its data, in the shard of
+/// that hash
+///
+/// The shards' locks don't poison, so this can be used to check that a panic while a shard was
+/// locked didn't leave it inconsistent
+///
+/// Note that every shard is locked in turn, so this is meant for diagnostics and not for hot paths
+pub fn health_check() -> bool {
+    POOL.set.health_check()
+}
+
+pub fn stats() -> Stats {
+    POOL.set.stats()
+}
+
+/// Returns [DetailedStats] about the entries of the pool, such as their total size and how they
+/// are spread between the shards
+///
+/// Note that every shard is locked in turn, so this is meant for diagnostics and not for hot paths
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, pool};
+///
+/// let _a = Interned::new(&[0; 1000]);
+///
+/// let stats = pool::detailed_stats();
+///
+/// assert!(stats.data_bytes >= 1000);
+/// assert!(stats.heap_bytes > stats.data_bytes);
+/// assert_eq!(stats.largest_entry, 1000);
+/// assert_eq!(stats.shard_entries.iter().sum::<usize>(), stats.entries);
+/// ```
+pub fn detailed_stats() -> DetailedStats {
+    POOL.set.detailed_stats()
+}
+
+/// Returns a counter that is bumped every time an entry is inserted to or removed from the pool
+///
+/// Comparing it to a previously returned value is a cheap way to tell whether the pool changed,
+/// e.g. to invalidate caches derived from the pool's entries
+///
+/// The counter is monotonic, but not synchronized with the pool's shards
+pub fn generation() -> u64 {
+    POOL.set.generation.load(Ordering::Relaxed)
+}
+
+/// Zeroes the [Stats] counters without touching the entries of the pool
+///
+/// Meant for tests that need to assert counter deltas from a known baseline
+#[cfg(any(test, feature = "test-util"))]
+pub fn reset_stats() {
+    POOL.set.reset_stats();
 }
@@ -0,0 +1,13 @@
+use quickcheck::{Arbitrary, Gen};
+
+use crate::interned::Interned;
+
+impl Arbitrary for Interned {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<u8>::arbitrary(g).into()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.to_vec().shrink().map(Self::from))
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::LazyLock,
+};
+
+use secrecy::{ExposeSecret, SecretBox, SecretString};
+
+use crate::{interned::Interned, pool::Pool};
+
+/// Kept apart from the global pool, see [SecretInterned]
+static SECRETS: LazyLock<Pool> = LazyLock::new(Pool::new);
+
+/// An [Interned] holding secret material, whose [Debug] and [Display] output is redacted
+///
+/// The data is only reachable through [ExposeSecret], so it's not printed by accident the way
+/// [Interned] prints its data, and is wiped once its last reference is dropped (see the `zeroize`
+/// feature)
+///
+/// Secrets are interned in a private pool rather than the global one, so they are never found by
+/// [Interned::existing] or enumerated by [pool::for_each], [pool::iter_snapshot],
+/// [pool::entries_matching] or [pool::byte_histogram] - note that interning still looks secrets up
+/// by comparing their data, which doesn't take constant time
+///
+/// [pool::for_each]: crate::pool::for_each
+/// [pool::iter_snapshot]: crate::pool::iter_snapshot
+/// [pool::entries_matching]: crate::pool::entries_matching
+/// [pool::byte_histogram]: crate::pool::byte_histogram
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::secrecy::SecretInterned;
+/// use secrecy::{ExposeSecret, SecretSlice};
+///
+/// let secret = SecretInterned::from(SecretSlice::from(b"hunter2".to_vec()));
+///
+/// assert_eq!(format!("{secret:?}"), "SecretInterned([REDACTED])");
+/// assert_eq!(&secret.expose_secret()[..], b"hunter2");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SecretInterned(Interned);
+
+impl SecretInterned {
+    /// Constructs a new [SecretInterned] for a given `value`
+    pub fn new(value: &[u8]) -> Self {
+        Self(Interned::new_in(&SECRETS, value))
+    }
+}
+
+impl ExposeSecret<Interned> for SecretInterned {
+    fn expose_secret(&self) -> &Interned {
+        &self.0
+    }
+}
+
+/// Keeps `value` in the pool it was interned in, see [SecretInterned::new] for keeping secrets
+/// out of the global pool
+impl From<Interned> for SecretInterned {
+    fn from(value: Interned) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SecretBox<[u8]>> for SecretInterned {
+    fn from(value: SecretBox<[u8]>) -> Self {
+        Self::new(value.expose_secret())
+    }
+}
+
+impl From<SecretString> for SecretInterned {
+    fn from(value: SecretString) -> Self {
+        Self::new(value.expose_secret().as_bytes())
+    }
+}
+
+impl Debug for SecretInterned {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("SecretInterned([REDACTED])")
+    }
+}
+
+impl Display for SecretInterned {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
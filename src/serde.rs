@@ -1,14 +1,58 @@
-use bstr::BString;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Formatter},
+    ops::Deref,
+};
 
-use crate::interned::Interned;
+use ::base64::{Engine, engine::general_purpose::STANDARD};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
+    ser::SerializeMap,
+};
+
+use crate::{
+    borrow::BorrowedInterned,
+    interned::Interned,
+    pool::Pool,
+    string::{BorrowedInternedStr, InternedStr},
+};
+
+/// The key of the map that non-UTF-8 data is written as in human-readable formats
+const BASE64_TAG: &str = "base64";
+
+/// In human-readable formats (such as JSON), data that is valid UTF-8 is written as a string,
+/// and other data as a map of `"base64"` to the data encoded as base64 - both are read back
+/// to the exact same data
+///
+/// Binary formats (such as MessagePack) write the data as a byte string
+impl Serialize for BorrowedInterned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // binary formats such as MessagePack store byte strings as is, instead of a sequence of
+        // numbers
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(self);
+        }
+
+        match std::str::from_utf8(self) {
+            Ok(value) => serializer.serialize_str(value),
+            Err(_) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(BASE64_TAG, &STANDARD.encode(&self[..]))?;
+                map.end()
+            }
+        }
+    }
+}
 
 impl Serialize for Interned {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        self.as_bstr().serialize(serializer)
+        self.deref().serialize(serializer)
     }
 }
 
@@ -17,6 +61,317 @@ impl<'de> Deserialize<'de> for Interned {
     where
         D: Deserializer<'de>,
     {
-        BString::deserialize(deserializer).map(|o| o.into())
+        InternedVisitor(Target::Global).deserialize(deserializer)
+    }
+}
+
+/// A [DeserializeSeed] of [Interned], for deserializing the same values over and over again
+///
+/// Borrowed input is looked up in the pool as is, so values that are already interned are
+/// returned without allocating - and only values that are not in the pool yet are allocated, same
+/// as [Interned::new]
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, serde::InternedSeed};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut deserializer = serde_json::Deserializer::from_str(r#""GET""#);
+/// let method = InternedSeed::new().deserialize(&mut deserializer).unwrap();
+///
+/// assert_eq!(method.as_ptr(), Interned::new(b"GET").as_ptr());
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct InternedSeed(Target);
+
+impl InternedSeed {
+    /// Constructs a new [InternedSeed] interning into the global pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new [InternedSeed] interning into the global pool through the per-thread
+    /// cache of [intern_cached]
+    ///
+    /// Hot values are returned without hashing them or locking a shard, at the cost of the cache
+    /// keeping its recent values alive on every deserializing thread until
+    /// [clear_thread_cache] is called
+    ///
+    /// [intern_cached]: crate::pool::intern_cached
+    /// [clear_thread_cache]: crate::pool::clear_thread_cache
+    pub fn cached() -> Self {
+        Self(Target::Cached)
+    }
+
+    /// Constructs a new [InternedSeed] interning into the given `pool`
+    pub fn in_pool(pool: &'static Pool) -> Self {
+        Self(Target::Pool(pool))
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for InternedSeed {
+    type Value = Interned;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        InternedVisitor(self.0).deserialize(deserializer)
+    }
+}
+
+/// Where deserialized values are interned
+#[derive(Clone, Copy, Default)]
+enum Target {
+    #[default]
+    Global,
+    Cached,
+    Pool(&'static Pool),
+}
+
+impl Target {
+    fn intern(self, value: &[u8]) -> Interned {
+        match self {
+            Target::Global => Interned::new(value),
+            Target::Cached => crate::pool::intern_cached(value),
+            Target::Pool(pool) => Interned::new_in(pool, value),
+        }
+    }
+}
+
+/// Reads the data written by [Interned]'s [Serialize] impl, as well as strings, byte strings
+/// and sequences of bytes in any format
+struct InternedVisitor(Target);
+
+impl InternedVisitor {
+    fn deserialize<'de, D>(self, deserializer: D) -> Result<Interned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // either way the data is interned straight from the input when the format lends it
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(self)
+        } else {
+            deserializer.deserialize_bytes(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for InternedVisitor {
+    type Value = Interned;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a string, a byte string, or a map of {BASE64_TAG:?} to base64"
+        )
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(self.0.intern(v.as_bytes()))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(self.0.intern(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut value = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(4096));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            value.push(byte);
+        }
+        Ok(self.0.intern(&value))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let Some((tag, encoded)) = map.next_entry::<String, String>()? else {
+            return Err(A::Error::invalid_length(0, &self));
+        };
+        if tag != BASE64_TAG {
+            return Err(A::Error::unknown_field(&tag, &[BASE64_TAG]));
+        }
+        if map.next_key::<serde::de::IgnoredAny>()?.is_some() {
+            return Err(A::Error::invalid_length(2, &self));
+        }
+
+        STANDARD
+            .decode(encoded)
+            .map(|o| self.0.intern(&o))
+            .map_err(A::Error::custom)
+    }
+}
+
+impl Serialize for BorrowedInternedStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+impl Serialize for InternedStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.deref().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Interned::deserialize(deserializer)?
+            .try_into()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serializes an [Interned] as a byte string in every format, for use with
+/// `#[serde(with = "intern_mint::serde::bytes")]`
+///
+/// Binary formats such as CBOR and MessagePack store the data as is, and human-readable formats
+/// that have no byte strings (such as JSON) write it as a sequence of numbers
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::Interned;
+///
+/// struct Payload(Interned);
+///
+/// impl serde::Serialize for Payload {
+///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         intern_mint::serde::bytes::serialize(&self.0, serializer)
+///     }
+/// }
+///
+/// let json = serde_json::to_string(&Payload(Interned::new(b"\xff\x00"))).unwrap();
+///
+/// assert_eq!(json, "[255,0]");
+/// ```
+pub mod bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::interned::Interned;
+
+    pub fn serialize<S>(value: &Interned, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Interned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Interned::deserialize(deserializer)
+    }
+}
+
+/// Reads a string in a binary-to-text encoding, see [hex], [base64] and [base64url]
+struct EncodedVisitor {
+    encoding: &'static str,
+    decode: fn(&str) -> Option<Vec<u8>>,
+}
+
+impl Visitor<'_> for EncodedVisitor {
+    type Value = Interned;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "a {} string", self.encoding)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        (self.decode)(v)
+            .map(Interned::from)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+}
+
+/// Serializes an [Interned] as a lowercase hex string, for use with
+/// `#[serde(with = "intern_mint::serde::hex")]`
+///
+/// Deserializing accepts uppercase hex as well
+pub mod hex {
+    use serde::{Deserializer, Serializer};
+
+    use super::EncodedVisitor;
+    use crate::interned::Interned;
+
+    pub fn serialize<S>(value: &Interned, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&::hex::encode(&value[..]))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Interned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(EncodedVisitor {
+            encoding: "hex",
+            decode: |o| ::hex::decode(o).ok(),
+        })
+    }
+}
+
+/// Serializes an [Interned] as a padded base64 string of the standard alphabet, for use with
+/// `#[serde(with = "intern_mint::serde::base64")]`
+pub mod base64 {
+    use ::base64::{Engine, engine::general_purpose::STANDARD};
+    use serde::{Deserializer, Serializer};
+
+    use super::EncodedVisitor;
+    use crate::interned::Interned;
+
+    pub fn serialize<S>(value: &Interned, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&STANDARD.encode(&value[..]))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Interned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(EncodedVisitor {
+            encoding: "base64",
+            decode: |o| STANDARD.decode(o).ok(),
+        })
+    }
+}
+
+/// Serializes an [Interned] as an unpadded base64 string of the URL-safe alphabet, for use with
+/// `#[serde(with = "intern_mint::serde::base64url")]`
+pub mod base64url {
+    use ::base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use serde::{Deserializer, Serializer};
+
+    use super::EncodedVisitor;
+    use crate::interned::Interned;
+
+    pub fn serialize<S>(value: &Interned, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&URL_SAFE_NO_PAD.encode(&value[..]))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Interned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(EncodedVisitor {
+            encoding: "base64url",
+            decode: |o| URL_SAFE_NO_PAD.decode(o).ok(),
+        })
     }
 }
@@ -0,0 +1,28 @@
+use std::ops::Deref;
+
+use sha2::{Digest, Sha256, digest::Output};
+
+use crate::borrow::BorrowedInterned;
+
+impl BorrowedInterned {
+    /// Computes the digest of the data with any hash function implementing [Digest]
+    pub fn digest<D: Digest>(&self) -> Output<D> {
+        D::digest(self.deref())
+    }
+
+    /// Computes the SHA-256 digest of the data
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    /// use sha2::{Digest, Sha256};
+    ///
+    /// let interned = Interned::new(b"hello");
+    ///
+    /// assert_eq!(interned.sha256(), *Sha256::digest(b"hello"));
+    /// ```
+    pub fn sha256(&self) -> [u8; 32] {
+        self.digest::<Sha256>().into()
+    }
+}
@@ -0,0 +1,71 @@
+use std::{borrow::Cow, ops::Deref};
+
+use speedy::{Context, Readable, Reader, Writable, Writer};
+
+use crate::{
+    borrow::BorrowedInterned,
+    interned::Interned,
+    string::{BorrowedInternedStr, InternedStr},
+};
+
+impl<C: Context> Writable<C> for BorrowedInterned {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        Writable::<C>::write_to(self.deref(), writer)
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Writable::<C>::bytes_needed(self.deref())
+    }
+}
+
+impl<C: Context> Writable<C> for Interned {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        Writable::<C>::write_to(self.deref(), writer)
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Writable::<C>::bytes_needed(self.deref())
+    }
+}
+
+/// Interns the data straight from the input when it's borrowed, and reads it into a temporary
+/// buffer otherwise
+impl<'a, C: Context> Readable<'a, C> for Interned {
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        Cow::<'a, [u8]>::read_from(reader).map(|o| Interned::new(&o))
+    }
+
+    fn minimum_bytes_needed() -> usize {
+        <Cow<'a, [u8]> as Readable<'a, C>>::minimum_bytes_needed()
+    }
+}
+
+impl<C: Context> Writable<C> for BorrowedInternedStr {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        Writable::<C>::write_to(self.deref(), writer)
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Writable::<C>::bytes_needed(self.deref())
+    }
+}
+
+impl<C: Context> Writable<C> for InternedStr {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        Writable::<C>::write_to(self.deref(), writer)
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Writable::<C>::bytes_needed(self.deref())
+    }
+}
+
+impl<'a, C: Context> Readable<'a, C> for InternedStr {
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        Cow::<'a, str>::read_from(reader).map(|o| InternedStr::new(&o))
+    }
+
+    fn minimum_bytes_needed() -> usize {
+        <Cow<'a, str> as Readable<'a, C>>::minimum_bytes_needed()
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{Debug, Display, Formatter},
+    hash::Hash,
+    ops::Deref,
+    str::Utf8Error,
+};
+
+use crate::{borrow::BorrowedInterned, interned::Interned, pool::POOL};
+
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+/// An [Interned] that is guaranteed to hold valid UTF-8, backed by the same pool
+///
+/// Values of [InternedStr] and [Interned] holding the same data share the same pointer
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, InternedStr};
+///
+/// let a = InternedStr::new("hello");
+/// let b = InternedStr::try_from(Interned::new(b"hello")).unwrap();
+///
+/// assert_eq!(a, b);
+/// assert_eq!(&**a, "hello");
+/// ```
+pub struct InternedStr(Interned);
+
+impl InternedStr {
+    /// Constructs a new [InternedStr] for a given `value`
+    pub fn new(value: &str) -> Self {
+        Self(POOL.intern_str(value))
+    }
+
+    /// Returns the underlying [Interned]
+    pub fn as_interned(&self) -> &Interned {
+        &self.0
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = BorrowedInternedStr;
+
+    fn deref(&self) -> &Self::Target {
+        BorrowedInternedStr::new(self.0.deref())
+    }
+}
+
+impl PartialOrd for InternedStr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InternedStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Display for InternedStr {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Display::fmt(self.deref(), f)
+    }
+}
+
+impl Debug for InternedStr {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Debug::fmt(self.deref(), f)
+    }
+}
+
+impl From<&str> for InternedStr {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl From<&String> for InternedStr {
+    fn from(value: &String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<Interned> for InternedStr {
+    type Error = Utf8Error;
+
+    fn try_from(value: Interned) -> Result<Self, Self::Error> {
+        std::str::from_utf8(value.deref())?;
+        Ok(Self(value))
+    }
+}
+
+impl From<InternedStr> for Interned {
+    fn from(value: InternedStr) -> Self {
+        value.0
+    }
+}
+
+impl Borrow<BorrowedInternedStr> for InternedStr {
+    fn borrow(&self) -> &BorrowedInternedStr {
+        self.deref()
+    }
+}
+
+impl AsRef<BorrowedInternedStr> for InternedStr {
+    fn as_ref(&self) -> &BorrowedInternedStr {
+        self.deref()
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+#[repr(transparent)]
+/// &[BorrowedInternedStr] is to [InternedStr] what &[BorrowedInterned] is to [Interned]
+///
+/// Hashing and comparing for equality use the pointer, the same as [BorrowedInterned]
+pub struct BorrowedInternedStr(BorrowedInterned);
+
+impl BorrowedInternedStr {
+    /// `value` must hold valid UTF-8
+    fn new(value: &BorrowedInterned) -> &BorrowedInternedStr {
+        unsafe { &*(value as *const BorrowedInterned as *const BorrowedInternedStr) }
+    }
+
+    /// Constructs back an [InternedStr] value from the given &[BorrowedInternedStr]
+    pub fn intern(&self) -> InternedStr {
+        // a new handle to the same entry, so its data is still the valid UTF-8 it was borrowed from
+        InternedStr(self.0.intern())
+    }
+
+    /// Returns the underlying &[BorrowedInterned]
+    pub fn as_borrowed_interned(&self) -> &BorrowedInterned {
+        &self.0
+    }
+}
+
+impl Default for &BorrowedInternedStr {
+    fn default() -> Self {
+        BorrowedInternedStr::new(Default::default())
+    }
+}
+
+impl Deref for BorrowedInternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        // only constructed from values that hold valid UTF-8
+        unsafe { std::str::from_utf8_unchecked(self.0.deref()) }
+    }
+}
+
+impl PartialOrd for BorrowedInternedStr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BorrowedInternedStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Display for BorrowedInternedStr {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Display::fmt(self.deref(), f)
+    }
+}
+
+impl Debug for BorrowedInternedStr {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Debug::fmt(self.deref(), f)
+    }
+}
+
+impl ToOwned for BorrowedInternedStr {
+    type Owned = InternedStr;
+
+    fn to_owned(&self) -> Self::Owned {
+        self.intern()
+    }
+}
@@ -0,0 +1,27 @@
+use std::ops::Deref;
+
+use subtle::ConstantTimeEq;
+
+use crate::borrow::BorrowedInterned;
+
+impl BorrowedInterned {
+    /// Compares the data to `other` in constant time, for comparing tokens or secrets without
+    /// leaking through timing how much of them matched
+    ///
+    /// Only the contents are compared in constant time - data of different lengths returns
+    /// early, so the length is not kept secret
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let token = Interned::new(b"s3cr3t");
+    ///
+    /// assert!(token.ct_eq(b"s3cr3t"));
+    /// assert!(!token.ct_eq(b"s3cr3T"));
+    /// ```
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        self.deref().ct_eq(other).into()
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use parking_lot::RwLock;
+
+use crate::{borrow::BorrowedInterned, interned::Interned};
+
+/// A compact key returned by [SymbolTable::intern], resolved back to the data with
+/// [SymbolTable::resolve]
+///
+/// Symbols are only meaningful to the table that returned them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the index of the symbol in its table, symbols are numbered densely from zero
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Default)]
+struct Symbols {
+    by_value: HashMap<Interned, Symbol>,
+    values: Vec<Interned>,
+}
+
+/// Maps interned values to 4 bytes [Symbol]s, for dense data structures where an [Interned]
+/// handle is too large, or where its reference counting is unwanted
+///
+/// The values are interned in the global pool, and the table keeps a handle to each of them until
+/// it is dropped
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::{Interned, SymbolTable};
+///
+/// let table = SymbolTable::new();
+///
+/// let a = table.intern(b"hello");
+/// let b = table.intern(b"hello");
+/// let c = table.intern(b"world");
+///
+/// assert_eq!(a, b);
+/// assert_ne!(a, c);
+/// assert_eq!(&table.resolve(a)[..], b"hello");
+/// assert_eq!(table.resolve(a).as_ptr(), Interned::new(b"hello").as_ptr());
+/// ```
+#[derive(Default)]
+pub struct SymbolTable(RwLock<Symbols>);
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the [Symbol] of `value`, assigning the next one if `value` is new to the table
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table already holds [u32::MAX] distinct values
+    pub fn intern(&self, value: &[u8]) -> Symbol {
+        self.intern_interned(Interned::new(value))
+    }
+
+    /// Same as [SymbolTable::intern], for a value that's already interned
+    pub fn intern_interned(&self, value: Interned) -> Symbol {
+        if let Some(&symbol) = self.0.read().by_value.get(&value) {
+            return symbol;
+        }
+
+        let mut symbols = self.0.write();
+        // checked again in case another thread inserted the value in between the locks
+        if let Some(&symbol) = symbols.by_value.get(&value) {
+            return symbol;
+        }
+
+        let symbol = Symbol(
+            symbols
+                .values
+                .len()
+                .try_into()
+                .expect("symbol table holds more than u32::MAX values"),
+        );
+        symbols.values.push(value.clone());
+        symbols.by_value.insert(value, symbol);
+        symbol
+    }
+
+    /// Returns the data of `symbol`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` wasn't returned by this table
+    pub fn resolve(&self, symbol: Symbol) -> &BorrowedInterned {
+        let symbols = self.0.read();
+        let value: &BorrowedInterned = symbols
+            .values
+            .get(symbol.0 as usize)
+            .expect("symbols must be resolved by the table that returned them");
+        // the data lives in the shared allocation rather than in the vector, and the table keeps
+        // its handle to it until the table itself is dropped
+        unsafe { &*(value as *const BorrowedInterned) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.read().values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
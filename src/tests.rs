@@ -4,12 +4,21 @@ use parking_lot::Mutex;
 use serial_test::serial;
 use triomphe::Arc;
 
-use crate::{BorrowedInterned, Interned, pool};
+use crate::{
+    BorrowedInterned, HierarchicalInterned, Interned,
+    pool::{self, POOL},
+};
 
 fn verify_empty() {
     // after default interned is used for the first time, it's kept forever in the pool
     // create a default instance in case it didn't exist before
     let _a = Interned::default();
+    // removed values of other pools (such as the nodes of `HierarchicalInterned`) may still hold
+    // their components until the epoch advances
+    #[cfg(feature = "epoch")]
+    for _ in 0..64 {
+        crossbeam_epoch::pin().flush();
+    }
     assert!(pool::len() == 1);
 }
 
@@ -163,6 +172,229 @@ fn map_usage_with_borrow() {
     verify_empty();
 }
 
+#[test]
+#[serial]
+fn is_ascii_and_utf8() {
+    {
+        let ascii = Interned::new(b"ascii");
+        let utf8 = Interned::from("ütf-8");
+        let invalid = Interned::new(b"\xff");
+
+        assert!(ascii.is_ascii() && ascii.is_utf8());
+        assert!(!utf8.is_ascii() && utf8.is_utf8());
+        assert!(!invalid.is_ascii() && !invalid.is_utf8());
+
+        let borrowed: &BorrowedInterned = &utf8;
+        assert!(!borrowed.is_ascii() && borrowed.is_utf8());
+        assert!(Interned::default().is_ascii() && Interned::default().is_utf8());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn parse() {
+    {
+        use crate::borrow::ParseError;
+
+        assert_eq!(Interned::new(b"42").parse::<u32>(), Ok(42));
+        assert_eq!(Interned::new(b"-1.5").parse::<f64>(), Ok(-1.5));
+        assert!(matches!(
+            Interned::new(b"x").parse::<u32>(),
+            Err(ParseError::Parse(_))
+        ));
+        assert!(matches!(
+            Interned::new(b" 42").parse::<u32>(),
+            Err(ParseError::Parse(_))
+        ));
+        assert!(matches!(
+            Interned::default().parse::<u32>(),
+            Err(ParseError::Parse(_))
+        ));
+        assert!(matches!(
+            Interned::new(b"\xff").parse::<u32>(),
+            Err(ParseError::Utf8(_))
+        ));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn to_os_string_and_path_buf() {
+    {
+        use std::path::Path;
+
+        let path = Path::new("/some/dir/file.txt");
+        let interned = Interned::from(path);
+        assert_eq!(interned.to_path_buf(), path);
+        assert_eq!(interned.to_os_string(), path.as_os_str());
+        assert_eq!(
+            Interned::from(interned.to_path_buf()).as_ptr(),
+            interned.as_ptr()
+        );
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+            let invalid_utf8 = OsStr::from_bytes(b"\xff\xfe");
+            let interned = Interned::from(invalid_utf8);
+            assert_eq!(interned.to_os_string(), invalid_utf8);
+        }
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn write_to() {
+    {
+        let interned = Interned::new(b"hello");
+
+        let mut buffer = Vec::new();
+        interned.write_to(&mut buffer).expect("write");
+        assert_eq!(buffer, b"hello");
+
+        let mut buffer = Vec::new();
+        interned.write_len_prefixed(&mut buffer).expect("write");
+        Interned::default()
+            .write_len_prefixed(&mut buffer)
+            .expect("write");
+        assert_eq!(buffer, b"\x05\0\0\0\0\0\0\0hello\0\0\0\0\0\0\0\0");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "bincode")]
+fn write_len_prefixed_matches_bincode_legacy() {
+    {
+        let config = bincode::config::legacy();
+        let interned = Interned::new(b"hello");
+
+        let mut buffer = Vec::new();
+        interned.write_len_prefixed(&mut buffer).expect("write");
+        assert_eq!(
+            buffer,
+            bincode::encode_to_vec(&interned, config).expect("encode")
+        );
+
+        let (decoded, read) =
+            bincode::decode_from_slice::<Interned, _>(&buffer, config).expect("decode");
+        assert_eq!(decoded.as_ptr(), interned.as_ptr());
+        assert_eq!(read, buffer.len());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn with_suffix_and_prefix() {
+    {
+        let base = Interned::new(b"base");
+
+        assert_eq!(
+            base.with_suffix(b"/1700000000").as_ptr(),
+            Interned::new(b"base/1700000000").as_ptr()
+        );
+        assert_eq!(
+            base.with_prefix(b"1700000000/").as_ptr(),
+            Interned::new(b"1700000000/base").as_ptr()
+        );
+        assert_eq!(base.with_suffix(b"").as_ptr(), base.as_ptr());
+        assert_eq!(base.with_prefix(b"").as_ptr(), base.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn intern_all_unique() {
+    {
+        const LEN: usize = 256;
+
+        let values = pool::intern_all_unique((0..LEN).map(|o| o.to_string().into_bytes()));
+        assert_eq!(values.len(), LEN);
+        assert_eq!(pool::count_data_duplicates(), 0);
+        assert!(pool::health_check());
+
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(Interned::from(i.to_string()).as_ptr(), value.as_ptr());
+        }
+
+        // breaking the precondition returns the existing entries instead of duplicating them
+        let len = pool::len();
+        let duplicates = pool::intern_all_unique([b"0".to_vec(), b"new".to_vec(), b"new".to_vec()]);
+        assert_eq!(duplicates[0].as_ptr(), values[0].as_ptr());
+        assert_eq!(duplicates[1].as_ptr(), duplicates[2].as_ptr());
+        assert_eq!(pool::len(), len + 1);
+        assert_eq!(pool::count_data_duplicates(), 0);
+        assert!(pool::health_check());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn intern_from_parts() {
+    {
+        let interned = pool::intern_from_parts(&[b"ab", b"cd"]);
+        assert_eq!(interned.as_ptr(), Interned::from("abcd").as_ptr());
+        assert_eq!(
+            pool::intern_from_parts(&[b"a", b"", b"bcd"]).as_ptr(),
+            interned.as_ptr()
+        );
+        assert_eq!(
+            pool::intern_from_parts(&[]).as_ptr(),
+            Interned::default().as_ptr()
+        );
+
+        let large = vec![b'x'; 10_000];
+        assert_eq!(
+            pool::intern_from_parts(&[&large[..5_000], &large[5_000..]]).as_ptr(),
+            Interned::new(&large).as_ptr()
+        );
+
+        assert_eq!(
+            Interned::join(b"/", &[b"", b"usr", b"bin"]).as_ptr(),
+            Interned::new(b"/usr/bin").as_ptr()
+        );
+        assert_eq!(&Interned::join(b", ", &[b"one"])[..], b"one");
+        assert!(Interned::join(b".", &[]).is_empty());
+
+        let key = Interned::concat(&[b"tenant", b"\0", b"path"]);
+        assert_eq!(&key[..], b"tenant\0path");
+        assert_eq!(
+            Interned::concat(&[b"tenant\0", b"path"]).as_ptr(),
+            key.as_ptr()
+        );
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn map_from_iter() {
+    {
+        let map = pool::map_from_iter([(b"key".as_slice(), 1), (b"other_key", 2)]);
+
+        let key = Interned::new(b"key");
+        assert_eq!(map.get(&key), Some(&1));
+
+        let borrowed_key: &BorrowedInterned = &key;
+        assert_eq!(map.get(borrowed_key), Some(&1));
+
+        let other_key = Interned::new(b"other_key");
+        assert_eq!(map.get(other_key.as_ref()), Some(&2));
+
+        let unknown_key = Interned::new(b"unknown_key");
+        assert_eq!(map.get(unknown_key.as_ref()), None);
+    }
+    verify_empty();
+}
+
 #[test]
 #[serial]
 fn btree_usage_with_borrow() {
@@ -199,52 +431,2420 @@ fn re_intern_borrow_same_ptr() {
 
 #[test]
 #[serial]
-fn validate_data_hash() {
-    let hash_builder = ahash::RandomState::new();
+fn compare_with_borrow() {
+    {
+        let a = Interned::new(b"a");
+        let b = Interned::new(b"b");
+        let borrowed_a: &BorrowedInterned = a.as_ref();
+        let borrowed_b: &BorrowedInterned = b.as_ref();
 
-    let hash_data = |data: &Interned| {
-        let mut hasher = hash_builder.build_hasher();
-        data.hash_data(&mut hasher);
-        hasher.finish()
-    };
+        assert!(a == *borrowed_a);
+        assert!(*borrowed_a == a);
+        assert!(a != *borrowed_b);
+        assert!(*borrowed_b != a);
 
-    let (ptr_hash_1, data_hash_1) = {
-        let interned = Interned::new(b"hello!");
-        (hash_builder.hash_one(&interned), hash_data(&interned))
-    };
+        assert!(a < *borrowed_b);
+        assert!(*borrowed_b > a);
+        assert!(a <= *borrowed_a);
+        assert!(*borrowed_a >= a);
+    }
     verify_empty();
+}
 
-    let (ptr_hash_2, data_hash_2) = {
-        let _a = Interned::new(b"a");
-        let _a = Interned::new(b"bit");
-        let _a = Interned::new(b"more");
-        let _a = Interned::new(b"allocations");
-        let _a = Interned::new(b"so");
-        let _a = Interned::new(b"we");
-        let _a = Interned::new(b"won't");
-        let _a = Interned::new(b"use");
-        let _a = Interned::new(b"the");
-        let _a = Interned::new(b"same");
-        let _a = Interned::new(b"address");
+#[test]
+#[serial]
+fn interned_str() {
+    {
+        use std::collections::{BTreeMap, HashMap};
 
-        let interned = Interned::new(b"hello!");
-        (hash_builder.hash_one(&interned), hash_data(&interned))
-    };
+        use crate::{BorrowedInternedStr, InternedStr};
+
+        let a = InternedStr::new("héllo");
+        let b = InternedStr::from(String::from("héllo"));
+        let c = InternedStr::try_from(Interned::from("héllo")).expect("valid utf-8");
+        assert_eq!(a, b);
+        assert_eq!(b, c);
+        assert_eq!(a.as_ptr(), Interned::from("héllo").as_ptr());
+        assert_eq!(&**a, "héllo");
+        assert_eq!(a.to_string(), "héllo");
+        assert_eq!(format!("{a:?}"), r#""héllo""#);
+
+        assert!(InternedStr::try_from(Interned::new(b"\xff")).is_err());
+        assert_eq!(&**InternedStr::default(), "");
+
+        let map = HashMap::<InternedStr, u64>::from_iter([(a.clone(), 1)]);
+        let borrowed: &BorrowedInternedStr = &b;
+        assert_eq!(map.get(borrowed), Some(&1));
+        assert_eq!(borrowed.intern(), a);
+
+        let map = BTreeMap::<InternedStr, u64>::from_iter([(a.clone(), 1)]);
+        assert_eq!(map.get(borrowed), Some(&1));
+        assert_eq!(map.get(InternedStr::new("other").as_ref()), None);
+    }
     verify_empty();
+}
 
-    assert_ne!(ptr_hash_1, data_hash_1);
-    assert_ne!(ptr_hash_1, ptr_hash_2);
+#[test]
+#[serial]
+fn interned_value() {
+    use crate::{InternedValue, value::pool_of};
 
-    assert_ne!(ptr_hash_2, data_hash_2);
-    assert_eq!(data_hash_1, data_hash_2);
+    #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    struct Parsed {
+        name: String,
+        id: u64,
+    }
+
+    {
+        let a = InternedValue::new(Parsed {
+            name: "a".into(),
+            id: 1,
+        });
+        let b = InternedValue::new(Parsed {
+            name: "a".into(),
+            id: 1,
+        });
+        let c = InternedValue::new(Parsed {
+            name: "c".into(),
+            id: 3,
+        });
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(std::ptr::eq(&*a, &*b));
+        assert!(a < c);
+        assert_eq!(a.name, "a");
+        assert_eq!(pool_of::<Parsed>().len(), 2);
+
+        let tuple = InternedValue::new((1, "one"));
+        assert_eq!(tuple, InternedValue::from((1, "one")));
+        assert_eq!(pool_of::<(i32, &str)>().len(), 1);
+
+        let map = std::collections::HashMap::from([(a.clone(), 1)]);
+        assert_eq!(map.get(&b), Some(&1));
+        assert_eq!(map.get(&c), None);
+    }
+    assert!(pool_of::<Parsed>().is_empty());
+    assert!(pool_of::<(i32, &str)>().is_empty());
+    verify_empty();
 }
 
 #[test]
 #[serial]
-#[cfg(feature = "serde")]
-fn serde() {
-    let a = Interned::new(b"hello");
-    let serialized = serde_json::to_string(&a).expect("serialize");
-    let b = serde_json::from_str::<Interned>(&serialized).expect("deserialize");
-    assert_eq!(a.as_ptr(), b.as_ptr());
+fn cached_hash() {
+    {
+        let large = vec![b'x'; 64 * 1024];
+        let a = Interned::new(&large);
+        assert_eq!(
+            a.header().hash,
+            POOL.set.hash_builder.hash_one(large.as_slice())
+        );
+
+        // growing the shards rehashes the entries by their cached hashes
+        pool::with_capacity_hint(pool::capacity() * 2);
+        assert!(pool::health_check());
+        assert_eq!(Interned::new(&large).as_ptr(), a.as_ptr());
+        pool::shrink_to_fit();
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn pin() {
+    // pinned entries are never removed, so keep them out of the global pool
+    let custom: &'static pool::Pool = Box::leak(Box::default());
+    {
+        let a = Interned::new_in(custom, b"well-known").pin();
+        assert!(a.is_pinned());
+        let a = a.pin();
+        assert_eq!(custom.stats().pinned, 1);
+
+        let ptr = a.as_ptr();
+        drop(a);
+        assert_eq!(custom.len(), 1);
+        assert_eq!(Interned::new_in(custom, b"well-known").as_ptr(), ptr);
+
+        let b = Interned::new_in(custom, b"not pinned");
+        assert!(!b.is_pinned());
+    }
+    assert_eq!(custom.len(), 1);
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn existing() {
+    {
+        assert!(Interned::existing(b"working set").is_none());
+        assert_eq!(pool::len(), 1);
+
+        let a = Interned::new(b"working set");
+        let b = Interned::existing(b"working set").unwrap();
+        assert_eq!(a.as_ptr(), b.as_ptr());
+
+        let custom: &'static pool::Pool = Box::leak(Box::default());
+        assert!(Interned::existing_in(custom, b"working set").is_none());
+        assert!(custom.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn iter_snapshot() {
+    {
+        let values = (0..100)
+            .map(|o| Interned::from(o.to_string()))
+            .collect::<Vec<_>>();
+
+        // the default value is kept in the pool as well
+        let _default = Interned::default();
+        let mut snapshot = pool::iter_snapshot().collect::<Vec<_>>();
+        assert_eq!(snapshot.len(), values.len() + 1);
+
+        snapshot.retain(|o| !o.is_empty());
+        snapshot.sort();
+        let mut values = values;
+        values.sort();
+        assert!(
+            snapshot
+                .iter()
+                .zip(&values)
+                .all(|(a, b)| a.as_ptr() == b.as_ptr())
+        );
+
+        // the snapshot keeps the entries alive on its own
+        drop(values);
+        assert_eq!(pool::iter_snapshot().count(), 101);
+        drop(snapshot);
+        assert_eq!(pool::iter_snapshot().count(), 1);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn retain() {
+    {
+        let kept = Interned::new(b"referenced");
+        let len = pool::len();
+
+        // leave unreferenced entries behind, as racing drops might
+        drop(POOL.set.insert_unique(b"stale a", &POOL));
+        drop(POOL.set.insert_unique(b"stale b", &POOL));
+        assert_eq!(pool::len(), len + 2);
+
+        assert_eq!(pool::retain(|o| o != b"stale a" && o != b"referenced"), 1);
+        assert!(Interned::existing(b"stale a").is_none());
+        assert_eq!(pool::len(), len + 1);
+
+        assert_eq!(pool::clear_unreferenced(), 1);
+        assert_eq!(pool::len(), len);
+        assert_eq!(
+            Interned::existing(b"referenced").unwrap().as_ptr(),
+            kept.as_ptr()
+        );
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn detailed_stats() {
+    {
+        let _default = Interned::default();
+        let before = pool::detailed_stats();
+
+        let _a = Interned::new(b"hello");
+        let _b = Interned::new(&[0; 100]);
+        let _c = Interned::new(b"hello");
+
+        let after = pool::detailed_stats();
+        assert_eq!(after.entries, before.entries + 2);
+        assert_eq!(after.data_bytes, before.data_bytes + 105);
+        assert!(after.heap_bytes >= before.heap_bytes + 105);
+        assert_eq!(after.largest_entry, before.largest_entry.max(100));
+        assert_eq!(after.shard_entries.len(), POOL.set.shards.len());
+        assert_eq!(after.shard_entries.iter().sum::<usize>(), after.entries);
+        assert_eq!(after.counters.hits, before.counters.hits + 1);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "metrics")]
+fn metrics() {
+    {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        let kept = metrics::with_local_recorder(&recorder, || {
+            let a = Interned::new(b"hello");
+            let b = Interned::new(b"hello");
+            drop((a, b));
+            Interned::new(b"world!")
+        });
+
+        let values = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| (key.key().name().to_owned(), value))
+            .collect::<std::collections::HashMap<_, _>>();
+
+        assert_eq!(values["intern_mint.insertions"], DebugValue::Counter(2));
+        assert_eq!(values["intern_mint.removals"], DebugValue::Counter(1));
+        assert_eq!(
+            values["intern_mint.pool.entries"],
+            DebugValue::Gauge(1.0.into())
+        );
+        assert_eq!(
+            values["intern_mint.pool.bytes"],
+            DebugValue::Gauge(6.0.into())
+        );
+
+        metrics::with_local_recorder(&recorder, || drop(kept));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "tracing")]
+fn tracing() {
+    {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use tracing::{Event, Metadata, span};
+
+        #[derive(Default)]
+        struct CountEvents(AtomicUsize);
+
+        impl tracing::Subscriber for CountEvents {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, _: &Event<'_>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                // the shard of the inserted entry must be unlocked by now
+                drop(Interned::new(&[0; 16]));
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let subscriber = std::sync::Arc::new(CountEvents::default());
+        crate::tracing::set_large_insert_threshold(16);
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let _small = Interned::new(b"small");
+            assert_eq!(subscriber.0.load(Ordering::Relaxed), 0);
+
+            let _large = Interned::new(&[0; 16]);
+            let _existing = Interned::new(&[0; 16]);
+            assert_eq!(subscriber.0.load(Ordering::Relaxed), 1);
+        });
+
+        crate::tracing::set_large_insert_threshold(64 * 1024);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "epoch")]
+fn epoch() {
+    {
+        let a = Interned::new(b"cached");
+        let hits = pool::stats().hits;
+
+        // found in the cache without locking the shard
+        let (_hash, _shard) = POOL.set.get_hash_and_shard(b"cached");
+        let b = Interned::new(b"cached");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert_eq!(pool::stats().hits, hits + 1);
+    }
+    verify_empty();
+
+    {
+        // values are constantly dropped and interned again while others look them up
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for i in 0..10_000 {
+                        let value = (i % 16).to_string();
+                        let a = Interned::new(value.as_bytes());
+                        let b = Interned::new(value.as_bytes());
+                        assert_eq!(a.as_ptr(), b.as_ptr());
+                        assert_eq!(
+                            Interned::existing(value.as_bytes()).map(|o| o.as_ptr()),
+                            Some(a.as_ptr())
+                        );
+                    }
+                });
+            }
+        });
+        assert!(pool::health_check());
+    }
+    verify_empty();
+
+    {
+        // entries sharing the only slot of their shard keep replacing each other
+        let small: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new()
+                .shard_amount(2)
+                .read_cache_slots(1)
+                .build(),
+        ));
+
+        let values = (0..64)
+            .map(|o| Interned::new_in(small, o.to_string().as_bytes()))
+            .collect::<Vec<_>>();
+        for (i, value) in values.iter().enumerate() {
+            let again = Interned::new_in(small, i.to_string().as_bytes());
+            assert_eq!(again.as_ptr(), value.as_ptr());
+        }
+        assert_eq!(small.len(), 64);
+
+        drop(values);
+        assert!(small.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn memory_limit() {
+    {
+        let unpooled: &'static pool::Pool =
+            Box::leak(Box::new(pool::PoolBuilder::new().memory_limit(10).build()));
+
+        let a = Interned::new_in(unpooled, b"8 bytes!");
+        let b = Interned::new_in(unpooled, b"3by");
+        let c = Interned::new_in(unpooled, b"3by");
+        assert_eq!(unpooled.len(), 1);
+        assert_ne!(b.as_ptr(), c.as_ptr());
+        assert_eq!(&b[..], b"3by");
+        assert_eq!(
+            Interned::try_new_limited_in(unpooled, b"3by").err(),
+            Some(pool::MemoryLimitExceeded)
+        );
+        assert_eq!(
+            Interned::try_new_limited_in(unpooled, b"8 bytes!")
+                .unwrap()
+                .as_ptr(),
+            a.as_ptr()
+        );
+
+        // borrowed unpooled values are turned back into the same allocation
+        let borrowed: &BorrowedInterned = &b;
+        let owned = borrowed.intern();
+        assert_eq!(owned.as_ptr(), b.as_ptr());
+        assert!(owned == b);
+        assert!(Interned::existing(b"3by").is_none());
+        drop(owned);
+
+        drop((b, c));
+        drop(a);
+        assert!(unpooled.is_empty());
+        assert_eq!(Interned::new_in(unpooled, b"3by").len(), 3);
+        assert_eq!(
+            unpooled
+                .set
+                .bytes
+                .load(std::sync::atomic::Ordering::Relaxed),
+            0
+        );
+
+        let evict: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new()
+                .memory_limit(10)
+                .on_overflow(pool::OverflowBehavior::Evict)
+                .build(),
+        ));
+
+        // an unreferenced entry, as racing drops might leave behind
+        drop(evict.set.insert_unique(b"stale", evict));
+        let d = Interned::new_in(evict, b"8 bytes!");
+        assert_eq!(evict.len(), 1);
+        assert_eq!(Interned::new_in(evict, b"8 bytes!").as_ptr(), d.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn max_entry_len() {
+    {
+        let custom: &'static pool::Pool =
+            Box::leak(Box::new(pool::PoolBuilder::new().max_entry_len(4).build()));
+
+        let short = Interned::new_in(custom, b"four");
+        assert!(Interned::try_new_limited_in(custom, b"four").is_ok());
+        assert!(Interned::try_new_limited_in(custom, b"five!").is_err());
+
+        let long = Interned::new_in(custom, b"five!");
+        assert_eq!(&long[..], b"five!");
+        assert_ne!(long.as_ptr(), Interned::new_in(custom, b"five!").as_ptr());
+        assert_eq!(custom.len(), 1);
+
+        drop((short, long));
+        assert!(custom.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn deferred_removal() {
+    {
+        let deferred: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new().deferred_removal(100).build(),
+        ));
+
+        let values = (0..150)
+            .map(|o| Interned::new_in(deferred, o.to_string().as_bytes()))
+            .collect::<Vec<_>>();
+        let kept = values[0].clone();
+        let requeued_ptr = values[149].as_ptr();
+        drop(values);
+
+        // the first 100 queued entries are swept in one batch, the referenced one isn't queued
+        assert_eq!(deferred.len(), 50);
+
+        // queued entries are reused until they are swept
+        let requeued = Interned::new_in(deferred, b"149");
+        assert_eq!(requeued.as_ptr(), requeued_ptr);
+        drop(requeued);
+
+        deferred.sweep();
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(
+            Interned::existing_in(deferred, b"0").unwrap().as_ptr(),
+            kept.as_ptr()
+        );
+
+        drop(kept);
+        assert_eq!(deferred.len(), 1);
+        deferred.sweep();
+        assert!(deferred.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+fn single_pointer() {
+    assert_eq!(size_of::<Interned>(), size_of::<usize>());
+    assert_eq!(size_of::<Option<Interned>>(), size_of::<usize>());
+}
+
+#[test]
+#[serial]
+fn new_in() {
+    let tenant: &'static pool::Pool = Box::leak(Box::default());
+    {
+        let a = Interned::new_in(tenant, b"hello");
+        let b = Interned::new_in(tenant, b"hello");
+        let global = Interned::new(b"hello");
+
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert_ne!(a.as_ptr(), global.as_ptr());
+        assert_eq!(tenant.len(), 1);
+        assert_eq!(pool::len(), 2);
+
+        // borrowed values are interned back into the pool they came from
+        let borrowed: &BorrowedInterned = &a;
+        assert_eq!(borrowed.intern().as_ptr(), a.as_ptr());
+        let borrowed: &BorrowedInterned = &global;
+        assert_eq!(borrowed.intern().as_ptr(), global.as_ptr());
+
+        drop(global);
+        assert_eq!(tenant.len(), 1);
+    }
+    assert!(tenant.is_empty());
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn ord_across_pools() {
+    use std::{cmp::Ordering, collections::BTreeSet};
+
+    use crate::InternedStr;
+
+    let tenant: &'static pool::Pool = Box::leak(Box::default());
+    {
+        let global = Interned::new(b"hello");
+        let other = Interned::new_in(tenant, b"hello");
+
+        // the same data in different pools is not equal, so it must not be ordered as equal
+        assert!(global != other);
+        assert_ne!(global.cmp(&other), Ordering::Equal);
+        assert_eq!(global.cmp(&other), other.cmp(&global).reverse());
+        assert_eq!(global.cmp(&global.clone()), Ordering::Equal);
+        assert_eq!(global.cmp(&Interned::new(b"world")), Ordering::Less);
+
+        let set = BTreeSet::from([global.clone(), other.clone(), global.clone()]);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&global));
+        assert!(set.contains(&other));
+
+        let global = InternedStr::try_from(global).unwrap();
+        let other = InternedStr::try_from(other).unwrap();
+        let set = BTreeSet::from([global.clone(), other.clone()]);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&*global));
+        assert!(set.contains(&*other));
+    }
+    assert!(tenant.is_empty());
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn pool_builder() {
+    {
+        let _a = Interned::new(b"hello");
+        assert_eq!(
+            pool::configure(pool::PoolBuilder::new()),
+            Err(pool::AlreadyInitialized)
+        );
+
+        let custom: &'static pool::Pool = Box::leak(Box::new(
+            pool::Pool::builder()
+                .shard_amount(4)
+                .capacity_per_shard(16)
+                .hash_builder(pool::RandomState::with_seeds(1, 2, 3, 4))
+                .build(),
+        ));
+        assert_eq!(custom.set.shards.len(), 4);
+        assert!(custom.capacity() >= 4 * 16);
+
+        let a = Interned::new_in(custom, b"hello");
+        let b = Interned::new_in(custom, b"hello");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert_eq!(custom.len(), 1);
+    }
+    verify_empty();
+}
+
+#[test]
+#[should_panic(expected = "power of two")]
+fn pool_builder_invalid_shard_amount() {
+    pool::PoolBuilder::new().shard_amount(3).build();
+}
+
+#[test]
+#[serial]
+fn symbol_table() {
+    {
+        use crate::SymbolTable;
+
+        let table = SymbolTable::new();
+        let tokens = [b"let".as_slice(), b"x", b"=", b"x"];
+        let symbols = tokens.map(|o| table.intern(o));
+
+        assert_eq!(symbols[1], symbols[3]);
+        assert_eq!(symbols.map(|o| o.to_u32()), [0, 1, 2, 1]);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.intern_interned(Interned::new(b"=")), symbols[2]);
+
+        let resolved = table.resolve(symbols[0]);
+        assert_eq!(&resolved[..], b"let");
+        assert_eq!(resolved.as_ptr(), Interned::new(b"let").as_ptr());
+        assert_eq!(pool::len(), 4);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn validate_data_hash() {
+    let hash_builder = ahash::RandomState::new();
+
+    let hash_data = |data: &Interned| {
+        let mut hasher = hash_builder.build_hasher();
+        data.hash_data(&mut hasher);
+        hasher.finish()
+    };
+
+    let (ptr_hash_1, data_hash_1) = {
+        let interned = Interned::new(b"hello!");
+        (hash_builder.hash_one(&interned), hash_data(&interned))
+    };
+    verify_empty();
+
+    let (ptr_hash_2, data_hash_2) = {
+        let _a = Interned::new(b"a");
+        let _a = Interned::new(b"bit");
+        let _a = Interned::new(b"more");
+        let _a = Interned::new(b"allocations");
+        let _a = Interned::new(b"so");
+        let _a = Interned::new(b"we");
+        let _a = Interned::new(b"won't");
+        let _a = Interned::new(b"use");
+        let _a = Interned::new(b"the");
+        let _a = Interned::new(b"same");
+        let _a = Interned::new(b"address");
+
+        let interned = Interned::new(b"hello!");
+        (hash_builder.hash_one(&interned), hash_data(&interned))
+    };
+    verify_empty();
+
+    assert_ne!(ptr_hash_1, data_hash_1);
+    assert_ne!(ptr_hash_1, ptr_hash_2);
+
+    assert_ne!(ptr_hash_2, data_hash_2);
+    assert_eq!(data_hash_1, data_hash_2);
+}
+
+#[test]
+#[serial]
+fn split_first_and_last() {
+    {
+        let interned = Interned::new(b"\x01payload\x02");
+
+        let (tag, rest) = interned.split_first().expect("not empty");
+        assert_eq!(tag, 1);
+        assert_eq!(rest.as_ptr(), Interned::new(b"payload\x02").as_ptr());
+
+        let (tag, rest) = interned.split_last().expect("not empty");
+        assert_eq!(tag, 2);
+        assert_eq!(rest.as_ptr(), Interned::new(b"\x01payload").as_ptr());
+
+        assert!(Interned::default().split_first().is_none());
+        assert!(Interned::default().split_last().is_none());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn split_on_separator() {
+    {
+        let value = Interned::new(b"a.b.c");
+
+        let (left, right) = value.rsplit_once(b'.').expect("separator exists");
+        assert_eq!(left.as_ptr(), Interned::new(b"a.b").as_ptr());
+        assert_eq!(right.as_ptr(), Interned::new(b"c").as_ptr());
+
+        let (left, right) = value.split_once(b'.').expect("separator exists");
+        assert_eq!(&left[..], b"a");
+        assert_eq!(&right[..], b"b.c");
+
+        assert!(value.rsplit_once(b'/').is_none());
+        assert!(value.split_once(b'/').is_none());
+
+        let parts = value.splitn(2, b'.').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(&parts[0][..], b"a");
+        assert_eq!(&parts[1][..], b"b.c");
+
+        let parts = value.splitn(10, b'.').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(value.splitn(0, b'.').count(), 0);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn replace() {
+    {
+        let path = Interned::new(b"C:\\dir\\file");
+        assert_eq!(
+            path.replace_byte(b'\\', b'/').as_ptr(),
+            Interned::new(b"C:/dir/file").as_ptr()
+        );
+        assert_eq!(path.replace_byte(b'!', b'/').as_ptr(), path.as_ptr());
+
+        let value = Interned::new(b"aXXbXXXc");
+        assert_eq!(&value.replace(b"XX", b"-")[..], b"a-b-Xc");
+        assert_eq!(&value.replace(b"XX", b"")[..], b"abXc");
+        assert_eq!(&value.replace(b"a", b"A")[..], b"AXXbXXXc");
+        assert_eq!(&value.replace(b"c", b"CC")[..], b"aXXbXXXCC");
+        assert_eq!(value.replace(b"YY", b"-").as_ptr(), value.as_ptr());
+        assert_eq!(value.replace(b"", b"-").as_ptr(), value.as_ptr());
+        assert_eq!(&Interned::new(b"XX").replace(b"XX", b"")[..], b"");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn index() {
+    {
+        let interned = Interned::new(b"hello!");
+        let borrowed: &BorrowedInterned = &interned;
+
+        assert_eq!(interned[0], b'h');
+        assert_eq!(&interned[2..5], b"llo");
+        assert_eq!(&interned[..2], b"he");
+        assert_eq!(&interned[4..], b"o!");
+        assert_eq!(&interned[1..=2], b"el");
+
+        assert_eq!(borrowed[5], b'!');
+        assert_eq!(&borrowed[2..5], b"llo");
+        assert_eq!(&borrowed[..], b"hello!");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[should_panic]
+fn index_out_of_bounds() {
+    let interned = Interned::new(b"hello!");
+    _ = interned[6];
+}
+
+#[test]
+#[serial]
+fn as_chunks() {
+    {
+        let interned = Interned::new(b"0123456789");
+
+        let (chunks, remainder) = interned.as_chunks::<4>();
+        assert_eq!(chunks, [*b"0123", *b"4567"]);
+        assert_eq!(remainder, b"89");
+
+        let empty = Interned::default();
+        let (chunks, remainder) = empty.as_chunks::<4>();
+        assert!(chunks.is_empty());
+        assert!(remainder.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn common_prefix_len() {
+    {
+        let interned = Interned::new(b"interned");
+        let internal = Interned::new(b"internal");
+        let empty = Interned::default();
+
+        assert_eq!(interned.common_prefix_len(&internal), 6);
+        assert_eq!(internal.common_prefix_len(&interned), 6);
+        assert_eq!(interned.common_prefix_len(&interned), 8);
+        assert_eq!(interned.common_prefix_len(&empty), 0);
+        assert_eq!(empty.common_prefix_len(&empty), 0);
+
+        let long = Interned::new(b"a long key spanning a few words/first");
+        let other = Interned::new(b"a long key spanning a few words/second");
+        let prefix = Interned::new(b"a long key spanning a few words");
+        assert_eq!(long.common_prefix_len(&other), 32);
+        assert_eq!(long.common_prefix_len(&prefix), 31);
+        assert_eq!(prefix.common_prefix_len(&other), 31);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn for_each() {
+    {
+        let values = [
+            Interned::new(b"a"),
+            Interned::new(b"few"),
+            Interned::new(b"values"),
+        ];
+
+        let (mut count, mut total_len) = (0, 0);
+        pool::for_each(|o| {
+            count += 1;
+            total_len += o.len();
+        });
+
+        // the default value is empty
+        assert_eq!(count, pool::len());
+        assert_eq!(total_len, values.iter().map(|o| o.len()).sum::<usize>());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn byte_histogram() {
+    {
+        let a = Interned::new(b"aab");
+        let _a = a.clone();
+        let _b = Interned::new(b"\0\xff");
+
+        let histogram = pool::byte_histogram();
+        assert_eq!(histogram[b'a' as usize], 2);
+        assert_eq!(histogram[b'b' as usize], 1);
+        assert_eq!(histogram[0], 1);
+        assert_eq!(histogram[0xff], 1);
+        assert_eq!(histogram.iter().sum::<u64>(), 5);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn count_data_duplicates() {
+    {
+        let _a = Interned::new(b"duplicated");
+        let _b = Interned::new(b"not duplicated");
+        assert_eq!(pool::count_data_duplicates(), 0);
+
+        // insert a standalone entry with the same data but a different pointer
+        let hash = POOL.set.hash_builder.hash_one(b"duplicated".as_slice());
+        let duplicate = pool::ShardedSet::new_entry(b"duplicated", hash, &POOL);
+        {
+            let (hash, mut shard) = POOL.set.get_hash_and_shard(&duplicate.slice);
+            shard.insert_unique(hash, duplicate.clone(), |o| POOL.set.hasher(o));
+        }
+        assert_eq!(pool::count_data_duplicates(), 1);
+
+        POOL.set.remove_if_needed(&duplicate);
+        assert_eq!(pool::count_data_duplicates(), 0);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn from_utf16() {
+    {
+        let utf16 = "héllo wörld 🦀".encode_utf16().collect::<Vec<_>>();
+        let interned = Interned::from_utf16(&utf16).expect("valid utf-16");
+        assert_eq!(interned.as_ptr(), Interned::from("héllo wörld 🦀").as_ptr());
+        assert_eq!(
+            Interned::from_utf16_lossy(&utf16).as_ptr(),
+            interned.as_ptr()
+        );
+
+        // unpaired surrogate
+        let invalid = [0x0061, 0xD800, 0x0062];
+        assert!(Interned::from_utf16(&invalid).is_err());
+        assert_eq!(
+            &Interned::from_utf16_lossy(&invalid)[..],
+            "a\u{FFFD}b".as_bytes()
+        );
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn entries_matching() {
+    {
+        let a = Interned::new(b"large value");
+        let _b = Interned::new(b"small value");
+        let _c = Interned::new(b"unrelated");
+        let _a = [a.clone(), a.clone()];
+
+        let mut entries = pool::entries_matching(|o| o.windows(5).any(|o| o == b"value"));
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0.as_ptr(), a.as_ptr());
+        assert_eq!(entries[0].1, 3);
+        assert_eq!(&entries[1].0[..], b"small value");
+        assert_eq!(entries[1].1, 1);
+
+        assert!(pool::entries_matching(|o| o.starts_with(b"missing")).is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn try_new() {
+    {
+        pool::reset_stats();
+
+        let a = Interned::try_new(b"hello").expect("insert");
+        let b = Interned::try_new(b"hello").expect("hit");
+        let c = Interned::new(b"hello");
+
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert_eq!(b.as_ptr(), c.as_ptr());
+        assert_eq!(pool::stats().inserts, 1);
+        assert_eq!(pool::stats().hits, 2);
+
+        pool::FAIL_ENTRY_ALLOCATION.set(true);
+        let failed = Interned::try_new(b"world");
+        // existing values don't allocate
+        let existing = Interned::try_new(b"hello");
+        pool::FAIL_ENTRY_ALLOCATION.set(false);
+
+        assert!(matches!(
+            failed,
+            Err(pool::TryReserveError::AllocError { .. })
+        ));
+        assert_eq!(existing.expect("hit").as_ptr(), a.as_ptr());
+        assert!(Interned::existing(b"world").is_none());
+        assert_eq!(pool::stats().inserts, 1);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn dedup_by_data() {
+    {
+        let a = Interned::new(b"a");
+        let b = Interned::new(b"b");
+        // same data as `a` but a different pointer, as if it didn't come from the pool
+        let hash = POOL.set.hash_builder.hash_one(b"a".as_slice());
+        let unpooled_a = Interned::from_existing(pool::ShardedSet::new_entry(b"a", hash, &POOL));
+        assert_ne!(a.as_ptr(), unpooled_a.as_ptr());
+
+        let values = pool::dedup_by_data([a.clone(), unpooled_a.clone(), b.clone(), a.clone()]);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].as_ptr(), a.as_ptr());
+        assert_eq!(values[1].as_ptr(), b.as_ptr());
+
+        let values = pool::dedup_by_data([unpooled_a.clone(), a.clone()]);
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].as_ptr(), unpooled_a.as_ptr());
+
+        assert!(pool::dedup_by_data([]).is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn health_check_after_panic() {
+    {
+        let values = (0..64)
+            .map(|o| Interned::from(o.to_string()))
+            .collect::<Vec<_>>();
+        assert!(pool::health_check());
+
+        // panic while constructing a new entry, under the lock of its shard
+        let result = std::thread::spawn(|| {
+            POOL.set.get_or_insert_with(b"panicked".as_slice(), |_, _| {
+                panic!("panic while inserting")
+            })
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert!(pool::health_check());
+        assert!(Interned::existing(b"panicked").is_none());
+        assert_eq!(&Interned::new(b"panicked")[..], b"panicked");
+        assert_eq!(Interned::new(b"0").as_ptr(), values[0].as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn health_check_hash_mismatch() {
+    {
+        let _a = Interned::new(b"a");
+        assert!(pool::health_check());
+
+        // an entry inserted with the hash of other data
+        let hash = POOL.set.hash_builder.hash_one(b"other".as_slice());
+        let corrupted = pool::ShardedSet::new_entry(b"corrupted", hash, &POOL);
+        POOL.set
+            .shard(hash)
+            .insert_unique(hash, corrupted.clone(), |o| POOL.set.hasher(o));
+        assert!(!pool::health_check());
+
+        POOL.set.remove_if_needed(&corrupted);
+        assert!(pool::health_check());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn with_capacity_hint() {
+    {
+        const LEN: usize = 10_000;
+
+        pool::shrink_to_fit();
+        pool::with_capacity_hint(LEN);
+        let capacity = pool::capacity();
+        assert!(capacity >= LEN);
+
+        let values = (0..LEN)
+            .map(|o| Interned::from(o.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(pool::capacity(), capacity);
+
+        drop(values);
+        pool::shrink_to_fit();
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn expected_distinct() {
+    {
+        const LEN: usize = 10_000;
+
+        let custom: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new().expected_distinct(LEN).build(),
+        ));
+        let capacity = custom.capacity();
+        assert!(capacity >= LEN);
+
+        let values = (0..LEN)
+            .map(|o| Interned::new_in(custom, o.to_string().as_bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(custom.capacity(), capacity);
+        assert_eq!(custom.len(), LEN);
+
+        // an explicit capacity per shard is kept when it's larger
+        let custom = pool::PoolBuilder::new()
+            .shard_amount(2)
+            .capacity_per_shard(LEN)
+            .expected_distinct(10)
+            .build();
+        assert!(custom.capacity() >= 2 * LEN);
+
+        drop(values);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn generation() {
+    {
+        let start = pool::generation();
+
+        let a = Interned::new(b"new generation");
+        let inserted = pool::generation();
+        assert!(inserted > start);
+
+        let b = Interned::new(b"new generation");
+        assert_eq!(pool::generation(), inserted);
+
+        drop(a);
+        assert_eq!(pool::generation(), inserted);
+
+        drop(b);
+        assert!(pool::generation() > inserted);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn shared_lookup() {
+    {
+        let a = Interned::new(b"shared");
+        let hash = POOL.set.hash_builder.hash_one(b"shared".as_slice());
+
+        // existing entries are found while the shard is locked for lookups
+        let _shard = POOL.set.shard_read(hash);
+        let b = Interned::new(b"shared");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert_eq!(Interned::existing(b"shared").unwrap().as_ptr(), a.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn format_interned() {
+    {
+        struct Nested;
+
+        impl std::fmt::Display for Nested {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                // interning while the outer value is being formatted
+                let inner = crate::format_interned!("{}-{}", "in", "ner");
+                f.write_str(std::str::from_utf8(&inner).unwrap())
+            }
+        }
+
+        let a = crate::format_interned!("{}:{}", "host", 80);
+        assert_eq!(&a[..], b"host:80");
+        assert_eq!(a.as_ptr(), Interned::new(b"host:80").as_ptr());
+
+        let literal = crate::format_interned!("literal");
+        assert_eq!(&literal[..], b"literal");
+
+        let nested = crate::format_interned!("<{}>", Nested);
+        assert_eq!(&nested[..], b"<in-ner>");
+
+        let long = crate::format_interned!("{}", "x".repeat(10_000));
+        assert_eq!(long.len(), 10_000);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn ascii_case() {
+    {
+        let mixed = Interned::new(b"Content-Type\xff");
+        let lower = mixed.to_ascii_lowercase_interned();
+        assert_eq!(&lower[..], b"content-type\xff");
+        assert_eq!(
+            &mixed.to_ascii_uppercase_interned()[..],
+            b"CONTENT-TYPE\xff"
+        );
+
+        // already in the requested case
+        let inserts = pool::stats().inserts;
+        assert_eq!(lower.to_ascii_lowercase_interned().as_ptr(), lower.as_ptr());
+        let borrowed: &BorrowedInterned = &lower;
+        assert_eq!(
+            borrowed.to_ascii_lowercase_interned().as_ptr(),
+            lower.as_ptr()
+        );
+        assert_eq!(pool::stats().inserts, inserts);
+
+        assert_eq!(
+            borrowed.to_ascii_uppercase_interned().as_ptr(),
+            Interned::new(b"CONTENT-TYPE\xff").as_ptr()
+        );
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn ascii_case_insensitive() {
+    {
+        let insensitive: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new()
+                .ascii_case_insensitive(true)
+                .build(),
+        ));
+
+        let a = Interned::new_in(insensitive, b"Content-Type");
+        assert_eq!(&a[..], b"content-type");
+        assert_eq!(
+            Interned::new_in(insensitive, b"CONTENT-TYPE").as_ptr(),
+            a.as_ptr()
+        );
+        assert_eq!(
+            Interned::existing_in(insensitive, b"content-TYPE")
+                .unwrap()
+                .as_ptr(),
+            a.as_ptr()
+        );
+        assert_eq!(
+            Interned::try_new_limited_in(insensitive, b"content-type")
+                .unwrap()
+                .as_ptr(),
+            a.as_ptr()
+        );
+
+        // only ASCII letters are folded
+        let upper = Interned::new_in(insensitive, "\u{c9}".as_bytes());
+        let lower = Interned::new_in(insensitive, "\u{e9}".as_bytes());
+        assert_ne!(upper.as_ptr(), lower.as_ptr());
+        drop((upper, lower));
+        assert_eq!(insensitive.len(), 1);
+
+        // the global pool stays case-sensitive
+        assert_ne!(Interned::new(b"A").as_ptr(), Interned::new(b"a").as_ptr());
+
+        drop(a);
+        assert!(insensitive.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn canonicalize() {
+    {
+        use std::borrow::Cow;
+
+        fn strip_trailing_slashes(value: &[u8]) -> Cow<'_, [u8]> {
+            let end = value.iter().rposition(|&o| o != b'/').map_or(0, |o| o + 1);
+            Cow::Borrowed(&value[..end])
+        }
+
+        let paths: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new()
+                .canonicalize(strip_trailing_slashes)
+                .ascii_case_insensitive(true)
+                .build(),
+        ));
+
+        let a = Interned::new_in(paths, b"C:/Windows//");
+        assert_eq!(&a[..], b"c:/windows");
+        assert_eq!(Interned::new_in(paths, b"c:/windows").as_ptr(), a.as_ptr());
+        assert_eq!(
+            Interned::existing_in(paths, b"C:/WINDOWS/")
+                .unwrap()
+                .as_ptr(),
+            a.as_ptr()
+        );
+        assert_eq!(paths.len(), 1);
+
+        drop(a);
+        assert!(paths.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn canonicalize_keeps_strings_valid() {
+    {
+        use std::borrow::Cow;
+
+        fn truncate(value: &[u8]) -> Cow<'_, [u8]> {
+            Cow::Borrowed(&value[..value.len().min(2)])
+        }
+
+        let truncated: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new().canonicalize(truncate).build(),
+        ));
+
+        // truncating the 3 bytes of the euro sign would leave invalid UTF-8
+        let euro = truncated.intern_str("€");
+        assert_eq!(&euro[..], "€".as_bytes());
+        assert_eq!(truncated.intern_str("€").as_ptr(), euro.as_ptr());
+
+        let ascii = truncated.intern_str("abc");
+        assert_eq!(&ascii[..], b"ab");
+        assert_eq!(Interned::new_in(truncated, b"abc").as_ptr(), ascii.as_ptr());
+
+        drop((euro, ascii));
+        assert!(truncated.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn borrowed_str_intern_keeps_entry() {
+    {
+        use std::borrow::Cow;
+
+        use crate::InternedStr;
+
+        fn truncate(value: &[u8]) -> Cow<'_, [u8]> {
+            Cow::Borrowed(&value[..value.len().min(2)])
+        }
+
+        // nothing is kept in the pool, so every value is unpooled
+        let truncated: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new()
+                .canonicalize(truncate)
+                .memory_limit(0)
+                .build(),
+        ));
+
+        let euro = InternedStr::try_from(truncated.intern_str("€")).unwrap();
+        let borrowed: &crate::BorrowedInternedStr = &euro;
+        let owned = borrowed.intern();
+        assert_eq!(owned.as_ptr(), euro.as_ptr());
+        assert_eq!(&**owned, "€");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn new_domain() {
+    {
+        let domain = Interned::new_domain(b"Mail.Example.COM.");
+        assert_eq!(&domain[..], b"mail.example.com");
+        assert_eq!(
+            Interned::new_domain(b"mail.example.com").as_ptr(),
+            domain.as_ptr()
+        );
+        // only a single trailing dot is stripped
+        assert_eq!(&Interned::new_domain(b"a..")[..], b"a.");
+        assert!(Interned::new_domain(b".").is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "idna")]
+fn new_domain_unicode() {
+    {
+        let decoded = Interned::new_domain_unicode(b"xn--bcher-kva.EXAMPLE.").unwrap();
+        assert_eq!(&decoded[..], "bücher.example".as_bytes());
+        assert_eq!(
+            Interned::new_domain_unicode("BÜCHER.example".as_bytes())
+                .unwrap()
+                .as_ptr(),
+            decoded.as_ptr()
+        );
+        assert!(Interned::new_domain_unicode(b"xn--a.example").is_err());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "bstr")]
+fn path_components() {
+    {
+        let a = Interned::new(b"/usr/lib/x86_64/").path_components();
+        let b = Interned::new(b"usr//lib/libc.so").path_components();
+
+        let names = a.iter().map(|o| &o[..]).collect::<Vec<_>>();
+        assert_eq!(names, [b"usr".as_slice(), b"lib", b"x86_64"]);
+        assert_eq!(a[0].as_ptr(), b[0].as_ptr());
+        assert_eq!(a[1].as_ptr(), b[1].as_ptr());
+        assert_eq!(&b[2][..], b"libc.so");
+
+        assert!(Interned::new(b"/").path_components().is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn intern_macro() {
+    {
+        // the empty value is kept in the pool forever anyway, see `verify_empty`
+        let empty = || crate::intern!(b"");
+        assert_eq!(empty().as_ptr(), empty().as_ptr());
+        assert_eq!(empty().as_ptr(), Interned::default().as_ptr());
+        assert_eq!(crate::intern!("").as_ptr(), empty().as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn read_lines() {
+    {
+        let input = b"first\nsecond\r\n\xff\n\nfirst";
+        let lines = Interned::read_lines(&input[..])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let expected: [&[u8]; 5] = [b"first", b"second", b"\xff", b"", b"first"];
+        assert_eq!(lines.len(), expected.len());
+        assert!(lines.iter().zip(expected).all(|(a, b)| &a[..] == b));
+        assert_eq!(lines[0].as_ptr(), lines[4].as_ptr());
+
+        assert_eq!(Interned::read_lines(&b""[..]).count(), 0);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn interned_iter() {
+    {
+        use crate::InternedIterExt;
+
+        let column = ["x", "x", "x", "y", "x"];
+        let hits = pool::stats().hits;
+
+        let interned = column.iter().interned().collect::<Vec<_>>();
+        assert_eq!(interned.len(), column.len());
+        assert!(
+            interned
+                .iter()
+                .zip(column)
+                .all(|(a, b)| &a[..] == b.as_bytes())
+        );
+        assert_eq!(interned[0].as_ptr(), interned[2].as_ptr());
+        assert_eq!(interned[0].as_ptr(), interned[4].as_ptr());
+        // only the last "x" is looked up in the pool again
+        assert_eq!(pool::stats().hits, hits + 1);
+
+        let owned = vec![b"x".to_vec(), b"z".to_vec()];
+        let interned_owned = owned.into_iter().interned().collect::<Vec<_>>();
+        assert_eq!(interned_owned[0].as_ptr(), interned[0].as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn intern_many() {
+    {
+        let existing = Interned::new(b"existing");
+        let inserts = pool::stats().inserts;
+
+        let inputs = (0..1000).map(|o| (o % 100).to_string()).collect::<Vec<_>>();
+        let mut inputs = inputs.iter().map(|o| o.as_bytes()).collect::<Vec<_>>();
+        inputs.push(b"existing");
+
+        let values = pool::intern_many(&inputs);
+        assert_eq!(values.len(), inputs.len());
+        for (value, input) in values.iter().zip(&inputs) {
+            assert_eq!(&value[..], *input);
+            assert_eq!(value.as_ptr(), Interned::new(input).as_ptr());
+        }
+        assert_eq!(values.last().unwrap().as_ptr(), existing.as_ptr());
+        assert_eq!(pool::stats().inserts, inserts + 100);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn intern_cached() {
+    {
+        let a = pool::intern_cached(b"hot");
+        let hits = pool::stats().hits;
+
+        // served from the thread's cache without looking in the pool
+        let b = pool::intern_cached(b"hot");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert_eq!(pool::stats().hits, hits);
+
+        // the cache is per thread
+        let ptr = a.as_ptr() as usize;
+        std::thread::spawn(move || {
+            assert_eq!(pool::intern_cached(b"hot").as_ptr() as usize, ptr);
+            pool::clear_thread_cache();
+        })
+        .join()
+        .unwrap();
+
+        // the oldest values are evicted
+        for i in 0..64 {
+            pool::intern_cached(i.to_string().as_bytes());
+        }
+        let len = pool::len();
+        drop((a, b));
+        assert_eq!(pool::len(), len - 1);
+
+        pool::clear_thread_cache();
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn try_len_when_locked() {
+    {
+        let _a = Interned::new(b"hello");
+        assert_eq!(pool::try_len(), Some(pool::len()));
+        assert!(pool::try_shard_memory_usage().iter().all(Option::is_some));
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || {
+            let (_hash, _shard) = POOL.set.get_hash_and_shard(b"hello");
+            locked_tx.send(()).expect("send");
+            release_rx.recv().expect("recv");
+        });
+        locked_rx.recv().expect("recv");
+
+        assert_eq!(pool::try_len(), None);
+        assert_eq!(
+            pool::try_shard_memory_usage()
+                .iter()
+                .filter(|o| o.is_none())
+                .count(),
+            1
+        );
+
+        release_tx.send(()).expect("send");
+        thread.join().expect("join");
+        assert_eq!(pool::try_len(), Some(pool::len()));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn reset_stats_keeps_entries() {
+    {
+        const LEN: usize = 16;
+
+        let values = (0..LEN)
+            .map(|o| Interned::from(o.to_string()))
+            .collect::<Vec<_>>();
+        let len = pool::len();
+
+        pool::reset_stats();
+        assert_eq!(pool::stats().hits, 0);
+        assert_eq!(pool::stats().inserts, 0);
+        assert_eq!(pool::len(), len);
+
+        let again = (0..LEN)
+            .map(|o| Interned::from(o.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(pool::stats().hits, LEN);
+        assert_eq!(pool::stats().inserts, 0);
+
+        pool::reset_stats();
+        let _new = Interned::new(b"not interned yet");
+        assert_eq!(pool::stats().hits, 0);
+        assert_eq!(pool::stats().inserts, 1);
+
+        drop((values, again));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde() {
+    let a = Interned::new(b"hello");
+    let serialized = serde_json::to_string(&a).expect("serialize");
+    let b = serde_json::from_str::<Interned>(&serialized).expect("deserialize");
+    assert_eq!(a.as_ptr(), b.as_ptr());
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_across_formats() {
+    {
+        use std::collections::HashMap;
+
+        use serde::{
+            Deserialize,
+            de::value::{BytesDeserializer, Error, StrDeserializer},
+        };
+
+        let env = Interned::new(b"production");
+
+        let from_str = Interned::deserialize(StrDeserializer::<Error>::new("production"))
+            .expect("deserialize");
+        assert_eq!(from_str.as_ptr(), env.as_ptr());
+
+        let from_bytes = Interned::deserialize(BytesDeserializer::<Error>::new(b"production"))
+            .expect("deserialize");
+        assert_eq!(from_bytes.as_ptr(), env.as_ptr());
+
+        let from_toml = toml::from_str::<HashMap<String, Interned>>(r#"env = "production""#)
+            .expect("deserialize");
+        assert_eq!(from_toml["env"].as_ptr(), env.as_ptr());
+
+        let from_json =
+            serde_json::from_str::<HashMap<String, Interned>>(r#"{"env":"production"}"#)
+                .expect("deserialize");
+        assert_eq!(from_json["env"].as_ptr(), env.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_interned_str() {
+    {
+        use crate::InternedStr;
+
+        let a = InternedStr::new("hello");
+        let serialized = serde_json::to_string(&a).expect("serialize");
+        assert_eq!(serialized, r#""hello""#);
+
+        let b = serde_json::from_str::<InternedStr>(&serialized).expect("deserialize");
+        assert_eq!(a, b);
+
+        assert!(serde_json::from_str::<InternedStr>("[255]").is_err());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_map_keys() {
+    {
+        use std::collections::{BTreeMap, HashMap};
+
+        let a = Interned::new(b"a");
+        let b = Interned::new(b"b");
+
+        let map = serde_json::from_str::<HashMap<Interned, u32>>(r#"{"a":1,"b":2}"#)
+            .expect("deserialize");
+        assert_eq!(map.get(&a), Some(&1));
+        assert_eq!(map.get(&b), Some(&2));
+        assert!(map.keys().any(|o| o.as_ptr() == a.as_ptr()));
+        assert!(map.keys().any(|o| o.as_ptr() == b.as_ptr()));
+
+        let map = serde_json::from_str::<BTreeMap<Interned, u32>>(r#"{"a":1,"b":2}"#)
+            .expect("deserialize");
+        assert_eq!(map.get(&a), Some(&1));
+        assert_eq!(map.get(&b), Some(&2));
+        assert!(map.keys().any(|o| o.as_ptr() == a.as_ptr()));
+        assert!(map.keys().any(|o| o.as_ptr() == b.as_ptr()));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "quickcheck")]
+fn quickcheck() {
+    fn round_trip(value: Vec<u8>) -> bool {
+        Interned::new(&value)[..] == value[..]
+    }
+
+    fn arbitrary_is_interned(value: Interned) -> bool {
+        Interned::new(&value).as_ptr() == value.as_ptr()
+    }
+
+    quickcheck::quickcheck(round_trip as fn(Vec<u8>) -> bool);
+    quickcheck::quickcheck(arbitrary_is_interned as fn(Interned) -> bool);
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "unicode-norm")]
+fn unicode_norm() {
+    {
+        let composed = pool::intern_nfc("caf\u{e9}");
+        let decomposed = pool::intern_nfc("cafe\u{301}");
+        assert_eq!(composed.as_ptr(), decomposed.as_ptr());
+        assert_eq!(composed.as_ptr(), Interned::from("caf\u{e9}").as_ptr());
+
+        let not_normalized = Interned::from("cafe\u{301}");
+        assert_ne!(not_normalized.as_ptr(), composed.as_ptr());
+        assert_eq!(not_normalized.normalized().as_ptr(), composed.as_ptr());
+        assert_eq!(composed.normalized().as_ptr(), composed.as_ptr());
+
+        let invalid = Interned::new(b"\xff\xfe");
+        assert_eq!(invalid.normalized().as_ptr(), invalid.as_ptr());
+
+        assert_eq!(Interned::new_nfc("cafe\u{301}").as_ptr(), composed.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "clap")]
+fn clap() {
+    {
+        use clap::{Arg, Command, value_parser};
+
+        let command = Command::new("test").arg(
+            Arg::new("name")
+                .long("name")
+                .value_parser(value_parser!(Interned)),
+        );
+
+        let matches = command
+            .try_get_matches_from(["test", "--name", "identifier"])
+            .expect("parse");
+        let name = matches.get_one::<Interned>("name").expect("name");
+        assert_eq!(name.as_ptr(), Interned::new(b"identifier").as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "sha2")]
+fn sha2() {
+    {
+        use sha2::{Digest, Sha256, Sha512};
+
+        let interned = Interned::new(b"content addressed");
+        assert_eq!(interned.sha256(), *Sha256::digest(b"content addressed"));
+        assert_eq!(
+            interned.digest::<Sha512>(),
+            Sha512::digest(b"content addressed")
+        );
+        assert_eq!(Interned::default().sha256(), *Sha256::digest(b""));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "rayon")]
+fn par_intern() {
+    {
+        const LEN: usize = 10_000;
+
+        let values = (0..LEN)
+            .map(|o| (o % 100).to_string().into_bytes())
+            .collect::<Vec<_>>();
+
+        let parallel = pool::par_intern(values.clone());
+        let sequential = values.into_iter().map(Interned::from).collect::<Vec<_>>();
+
+        assert_eq!(parallel.len(), LEN);
+        assert!(
+            parallel
+                .iter()
+                .zip(&sequential)
+                .all(|(a, b)| a.as_ptr() == b.as_ptr())
+        );
+        assert_eq!(pool::count_data_duplicates(), 0);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn hierarchical_interned() {
+    {
+        let a = HierarchicalInterned::new(b"/usr/lib/libc.so");
+        let b = HierarchicalInterned::new(b"/usr/lib/libm.so");
+        let c = HierarchicalInterned::new(b"/usr/lib/libc.so");
+
+        assert_eq!(a, c);
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), b.parent());
+        assert_eq!(a.parent(), Some(&HierarchicalInterned::new(b"/usr/lib")));
+        assert_eq!(a.component().as_ptr(), Interned::new(b"libc.so").as_ptr());
+        assert_eq!(a.depth(), 4);
+        assert_eq!(a.to_bytes(), b"/usr/lib/libc.so");
+        assert_eq!(&a.to_interned()[..], b"/usr/lib/libc.so");
+        assert_eq!(a.parent().unwrap().join(b"libm.so"), b);
+
+        let trailing = HierarchicalInterned::new(b"usr//lib/");
+        assert_eq!(trailing.depth(), 4);
+        assert_eq!(trailing.to_bytes(), b"usr//lib/");
+        assert_eq!(HierarchicalInterned::new(b"").to_bytes(), b"");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn hierarchical_interned_churn() {
+    {
+        // with the `epoch` feature, the nodes removed here are released later by whichever lookup
+        // runs the deferred releases, which drops the nodes' parents as well
+        let root = HierarchicalInterned::new(b"/var/log");
+        for i in 0..4096 {
+            let leaf = root.join(format!("{i}").as_bytes()).join(b"current");
+            assert_eq!(leaf.depth(), 5);
+        }
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "lz4")]
+fn compressed_interned() {
+    use crate::CompressedInterned;
+
+    {
+        let script = b"echo hello\n".repeat(1000);
+        let a = CompressedInterned::new(&script);
+        let b = CompressedInterned::new(&script);
+        assert_eq!(a, b);
+        assert!(a.is_compressed());
+        assert!(a.stored_len() < script.len());
+        assert_eq!(&*a.get(), &script[..]);
+
+        let small = CompressedInterned::new(b"echo hello");
+        assert!(!small.is_compressed());
+        assert!(matches!(
+            small.get(),
+            std::borrow::Cow::Borrowed(b"echo hello")
+        ));
+
+        // incompressible values are kept as is
+        let random = (0..64u32)
+            .map(|o| (o.wrapping_mul(2654435761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let random = CompressedInterned::with_threshold(&random, 0);
+        assert!(!random.is_compressed());
+        assert_eq!(random.stored_len(), 65);
+
+        let empty = CompressedInterned::with_threshold(b"", 0);
+        assert_eq!(&*empty.get(), b"");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "lz4")]
+fn compressed_interned_ignores_canonicalize() {
+    use std::borrow::Cow;
+
+    use crate::CompressedInterned;
+
+    {
+        fn truncate(value: &[u8]) -> Cow<'_, [u8]> {
+            Cow::Borrowed(&value[..value.len().min(2)])
+        }
+
+        let hooked: &'static pool::Pool = Box::leak(Box::new(
+            pool::PoolBuilder::new()
+                .canonicalize(truncate)
+                .ascii_case_insensitive(true)
+                .build(),
+        ));
+
+        let small = CompressedInterned::with_threshold_in(hooked, b"HELLO", usize::MAX);
+        assert_eq!(&*small.get(), b"HELLO");
+
+        let script = b"ECHO HELLO\n".repeat(1000);
+        let large = CompressedInterned::with_threshold_in(hooked, &script, 0);
+        assert!(large.is_compressed());
+        assert_eq!(&*large.get(), &script[..]);
+
+        // plain values are still canonicalized
+        assert_eq!(&Interned::new_in(hooked, b"HELLO")[..], b"he");
+
+        drop((small, large));
+        assert!(hooked.is_empty());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "zeroize")]
+fn zeroize() {
+    use crate::pool::Handle;
+
+    {
+        let mut entry = POOL.unpooled(b"secret");
+        let mut cloned = entry.clone();
+
+        // still referenced by `entry`
+        cloned.wipe_if_unique();
+        assert_eq!(&cloned.slice, b"secret");
+        drop(cloned);
+
+        entry.wipe_if_unique();
+        assert_eq!(&entry.slice, &[0; 6]);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "subtle")]
+fn ct_eq() {
+    {
+        let token = Interned::new(b"s3cr3t");
+        assert!(token.ct_eq(b"s3cr3t"));
+        assert!(!token.ct_eq(b"s3cr3T"));
+        assert!(!token.ct_eq(b"s3cr3"));
+        assert!(Interned::default().ct_eq(b""));
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "secrecy")]
+fn secret_interned() {
+    use secrecy::{ExposeSecret, SecretSlice, SecretString};
+
+    use crate::secrecy::SecretInterned;
+
+    {
+        let a = SecretInterned::from(SecretSlice::from(b"hunter2".to_vec()));
+        let b = SecretInterned::from(SecretString::from("hunter2"));
+        assert_eq!(a, b);
+        assert_eq!(a, SecretInterned::new(b"hunter2"));
+
+        // kept out of the global pool
+        assert!(Interned::existing(b"hunter2").is_none());
+        assert!(pool::entries_matching(|o| o == b"hunter2").is_empty());
+        assert_ne!(
+            a.expose_secret().as_ptr(),
+            Interned::new(b"hunter2").as_ptr()
+        );
+
+        assert_eq!(format!("{a:?}"), "SecretInterned([REDACTED])");
+        assert_eq!(a.to_string(), "[REDACTED]");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn weak_interned() {
+    {
+        let interned = Interned::new(b"weak");
+        let weak = interned.downgrade();
+        assert_eq!(weak, interned.clone().downgrade());
+        assert_ne!(weak, Interned::new(b"other").downgrade());
+
+        let upgraded = weak.upgrade().unwrap();
+        assert_eq!(upgraded.as_ptr(), interned.as_ptr());
+
+        drop((interned, upgraded));
+        assert_eq!(pool::len(), 1);
+        assert!(weak.upgrade().is_none());
+
+        // the same data may be allocated at the same address again, which upgrades to it
+        let again = Interned::new(b"weak");
+        if let Some(upgraded) = weak.upgrade() {
+            assert_eq!(upgraded.as_ptr(), again.as_ptr());
+        }
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn std_arc() {
+    {
+        let arc: std::sync::Arc<[u8]> = std::sync::Arc::from(b"shared".as_slice());
+        let interned = Interned::from(&arc);
+        assert_eq!(interned.as_ptr(), Interned::from(arc).as_ptr());
+
+        let arc = std::sync::Arc::<[u8]>::from(&interned);
+        assert_eq!(&arc[..], b"shared");
+        assert_ne!(arc.as_ptr(), interned.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+fn triomphe_arc() {
+    {
+        let arc = Arc::<[u8]>::from(b"shared".as_slice());
+        let interned = Interned::new(b"shared");
+        assert_eq!(Interned::from_arc(arc.clone()).as_ptr(), interned.as_ptr());
+
+        let arc = interned.clone().into_arc();
+        assert_eq!(&arc[..], b"shared");
+        drop(interned);
+        assert_eq!(&arc[..], b"shared");
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "bytes")]
+fn bytes() {
+    {
+        let interned = Interned::new(b"payload");
+        let bytes = interned.to_bytes();
+        assert_eq!(bytes.as_ptr(), interned.as_ptr());
+        assert_eq!(Interned::from(&bytes).as_ptr(), interned.as_ptr());
+
+        // the entry is kept alive by `bytes`
+        drop(interned);
+        assert_eq!(pool::len(), 2);
+        assert_eq!(&bytes.slice(3..)[..], b"load");
+        assert_eq!(
+            Interned::from(bytes).as_ptr(),
+            Interned::new(b"payload").as_ptr()
+        );
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "tokio")]
+fn interned_codec() {
+    use ::bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use crate::tokio::InternedCodec;
+
+    {
+        let mut codec = InternedCodec::new().max_frame_length(8);
+        let mut buffer = BytesMut::new();
+        codec.encode(&Interned::new(b"first"), &mut buffer).unwrap();
+        codec.encode(Interned::new(b"second"), &mut buffer).unwrap();
+        assert!(
+            codec
+                .encode(&Interned::new(b"too long!"), &mut buffer)
+                .is_err()
+        );
+
+        // the second frame arrives in two reads
+        let mut tail = buffer.split_off(12);
+        let first = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(first.as_ptr(), Interned::new(b"first").as_ptr());
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+
+        buffer.unsplit(tail.split());
+        assert_eq!(&codec.decode(&mut buffer).unwrap().unwrap()[..], b"second");
+        assert!(buffer.is_empty());
+
+        buffer.extend_from_slice(b"\0\0\0\x09too long!");
+        assert!(codec.decode(&mut buffer).is_err());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "borsh")]
+fn borsh() {
+    {
+        use crate::InternedStr;
+
+        let a = Interned::new(b"\xffhello");
+        let encoded = borsh::to_vec(&a).expect("serialize");
+        assert_eq!(encoded, borsh::to_vec(&b"\xffhello".to_vec()).unwrap());
+        let b = borsh::from_slice::<Interned>(&encoded).expect("deserialize");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+
+        let s = InternedStr::new("hello");
+        let encoded = borsh::to_vec(&s).expect("serialize");
+        assert_eq!(encoded, borsh::to_vec("hello").unwrap());
+        assert_eq!(borsh::from_slice::<InternedStr>(&encoded).unwrap(), s);
+        assert!(borsh::from_slice::<InternedStr>(&borsh::to_vec(&a).unwrap()).is_err());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "bincode")]
+fn bincode() {
+    {
+        use crate::InternedStr;
+
+        let config = bincode::config::standard();
+
+        let a = Interned::new(b"\xffhello");
+        let encoded = bincode::encode_to_vec(&a, config).expect("encode");
+        assert_eq!(
+            encoded,
+            bincode::encode_to_vec(b"\xffhello".as_slice(), config).unwrap()
+        );
+
+        let (b, read) =
+            bincode::decode_from_slice::<Interned, _>(&encoded, config).expect("decode");
+        assert_eq!((a.as_ptr(), encoded.len()), (b.as_ptr(), read));
+        let (b, _) = bincode::borrow_decode_from_slice::<Interned, _>(&encoded, config).unwrap();
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        let b: Interned = bincode::decode_from_std_read(&mut &encoded[..], config).unwrap();
+        assert_eq!(a.as_ptr(), b.as_ptr());
+
+        let s = InternedStr::new("hello");
+        let encoded = bincode::encode_to_vec(&s, config).unwrap();
+        assert_eq!(encoded, bincode::encode_to_vec("hello", config).unwrap());
+        let (t, _) = bincode::decode_from_slice::<InternedStr, _>(&encoded, config).unwrap();
+        assert_eq!(s, t);
+        let encoded = bincode::encode_to_vec(&a, config).unwrap();
+        assert!(bincode::decode_from_slice::<InternedStr, _>(&encoded, config).is_err());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_postcard() {
+    {
+        use crate::InternedStr;
+
+        let a = Interned::new(b"\xffhello");
+        let encoded = postcard::to_allocvec(&a).expect("serialize");
+        // a varint length followed by the bytes as is
+        assert_eq!(encoded, b"\x06\xffhello");
+        let b = postcard::from_bytes::<Interned>(&encoded).expect("deserialize");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+
+        let s = InternedStr::new("hello");
+        let encoded = postcard::to_allocvec(&s).expect("serialize");
+        assert_eq!(encoded, b"\x05hello");
+        assert_eq!(postcard::from_bytes::<InternedStr>(&encoded).unwrap(), s);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "speedy")]
+fn speedy() {
+    {
+        use speedy::{Readable, Writable};
+
+        use crate::InternedStr;
+
+        let a = Interned::new(b"\xffhello");
+        let encoded = a.write_to_vec().expect("write");
+        assert_eq!(encoded, b"\xffhello".to_vec().write_to_vec().unwrap());
+        assert_eq!((*a).write_to_vec().unwrap(), encoded);
+        let b = Interned::read_from_buffer(&encoded).expect("read");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        let b = Interned::read_from_stream_unbuffered(&encoded[..]).expect("read");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+
+        let s = InternedStr::new("hello");
+        let encoded = s.write_to_vec().unwrap();
+        assert_eq!(encoded, "hello".write_to_vec().unwrap());
+        assert_eq!(InternedStr::read_from_buffer(&encoded).unwrap(), s);
+        assert!(InternedStr::read_from_buffer(&a.write_to_vec().unwrap()).is_err());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_bytes_module() {
+    {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        struct Payload<'a>(&'a Interned);
+
+        impl Serialize for Payload<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                crate::serde::bytes::serialize(self.0, serializer)
+            }
+        }
+
+        struct Decoded(Interned);
+
+        impl<'de> Deserialize<'de> for Decoded {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::serde::bytes::deserialize(deserializer).map(Decoded)
+            }
+        }
+
+        let a = Interned::new(b"\xff\x00hello");
+        let mut encoded = Vec::new();
+        ciborium::into_writer(&Payload(&a), &mut encoded).expect("serialize");
+        // major type 2 - a byte string of 7 bytes
+        assert_eq!(encoded, b"\x47\xff\x00hello");
+
+        let b = ciborium::from_reader::<Decoded, _>(&encoded[..]).expect("deserialize");
+        assert_eq!(a.as_ptr(), b.0.as_ptr());
+
+        let json = serde_json::to_string(&Payload(&a)).unwrap();
+        assert_eq!(json, "[255,0,104,101,108,108,111]");
+        let b = serde_json::from_str::<Decoded>(&json).expect("deserialize");
+        assert_eq!(a.as_ptr(), b.0.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_msgpack() {
+    {
+        let a = Interned::new(b"\xff\x00hello");
+        let encoded = rmp_serde::to_vec(&a).expect("serialize");
+        // bin 8 with a length of 7
+        assert_eq!(encoded, b"\xc4\x07\xff\x00hello");
+        let b = rmp_serde::from_slice::<Interned>(&encoded).expect("deserialize");
+        assert_eq!(a.as_ptr(), b.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_human_readable() {
+    {
+        use std::collections::HashMap;
+
+        let text = Interned::new(b"hello");
+        let json = serde_json::to_string(&text).expect("serialize");
+        assert_eq!(json, r#""hello""#);
+        assert_eq!(
+            serde_json::from_str::<Interned>(&json).unwrap().as_ptr(),
+            text.as_ptr()
+        );
+
+        let binary = Interned::new(b"\xff\x00hi");
+        let json = serde_json::to_string(&binary).expect("serialize");
+        assert_eq!(json, r#"{"base64":"/wBoaQ=="}"#);
+        assert_eq!(
+            serde_json::from_str::<Interned>(&json).unwrap().as_ptr(),
+            binary.as_ptr()
+        );
+
+        // sequences of bytes are still accepted
+        let from_seq = serde_json::from_str::<Interned>("[255,0,104,105]").unwrap();
+        assert_eq!(from_seq.as_ptr(), binary.as_ptr());
+
+        assert!(serde_json::from_str::<Interned>(r#"{"hex":"ff"}"#).is_err());
+        assert!(serde_json::from_str::<Interned>(r#"{"base64":"!"}"#).is_err());
+        assert!(serde_json::from_str::<Interned>(r#"{"base64":"","base64":""}"#).is_err());
+
+        let map = HashMap::from([(text.clone(), 1)]);
+        assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"hello":1}"#);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_encoding_modules() {
+    {
+        use serde::{Deserializer, Serialize, Serializer};
+
+        struct Id(Interned, &'static str);
+
+        impl Serialize for Id {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self.1 {
+                    "hex" => crate::serde::hex::serialize(&self.0, serializer),
+                    "base64" => crate::serde::base64::serialize(&self.0, serializer),
+                    _ => crate::serde::base64url::serialize(&self.0, serializer),
+                }
+            }
+        }
+
+        fn decode<'de, D: Deserializer<'de>>(
+            deserializer: D,
+            encoding: &str,
+        ) -> Result<Interned, D::Error> {
+            match encoding {
+                "hex" => crate::serde::hex::deserialize(deserializer),
+                "base64" => crate::serde::base64::deserialize(deserializer),
+                _ => crate::serde::base64url::deserialize(deserializer),
+            }
+        }
+
+        let id = Interned::new(b"\xfb\xff\x00");
+        for (encoding, expected) in [
+            ("hex", r#""fbff00""#),
+            ("base64", r#""+/8A""#),
+            ("base64url", r#""-_8A""#),
+        ] {
+            let json = serde_json::to_string(&Id(id.clone(), encoding)).expect("serialize");
+            assert_eq!(json, expected);
+
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            let decoded = decode(&mut deserializer, encoding).expect("deserialize");
+            assert_eq!(decoded.as_ptr(), id.as_ptr());
+
+            let mut deserializer = serde_json::Deserializer::from_str(r#""!""#);
+            assert!(decode(&mut deserializer, encoding).is_err());
+        }
+
+        let upper = crate::serde::hex::deserialize(serde_json::Value::from("FBFF00")).unwrap();
+        assert_eq!(upper.as_ptr(), id.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_visitor() {
+    {
+        use serde::{
+            Deserialize,
+            de::{
+                IntoDeserializer,
+                value::{BorrowedBytesDeserializer, Error, SeqDeserializer},
+            },
+        };
+
+        let a = Interned::new(b"\xffhello");
+
+        let borrowed = Interned::deserialize(BorrowedBytesDeserializer::<Error>::new(b"\xffhello"))
+            .expect("deserialize");
+        assert_eq!(borrowed.as_ptr(), a.as_ptr());
+
+        let seq = SeqDeserializer::<_, Error>::new(a.iter().copied());
+        assert_eq!(Interned::deserialize(seq).unwrap().as_ptr(), a.as_ptr());
+
+        let owned = IntoDeserializer::<Error>::into_deserializer("hello".to_owned());
+        let owned = Interned::deserialize(owned).expect("deserialize");
+        assert_eq!(&owned[..], b"hello");
+
+        // MessagePack strings are accepted as well as binary payloads
+        let encoded = rmp_serde::to_vec("hello").unwrap();
+        let b = rmp_serde::from_slice::<Interned>(&encoded).expect("deserialize");
+        assert_eq!(b.as_ptr(), owned.as_ptr());
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_seed() {
+    {
+        use serde::de::DeserializeSeed;
+
+        use crate::serde::InternedSeed;
+
+        let method = Interned::new(b"GET");
+        for seed in [InternedSeed::new(), InternedSeed::cached()] {
+            for _ in 0..2 {
+                let mut deserializer = serde_json::Deserializer::from_str(r#""GET""#);
+                let deserialized = seed.deserialize(&mut deserializer).unwrap();
+                assert_eq!(deserialized.as_ptr(), method.as_ptr());
+            }
+        }
+
+        let custom = Box::leak(Box::new(pool::PoolBuilder::new().build()));
+        let mut deserializer = serde_json::Deserializer::from_str(r#""GET""#);
+        let deserialized = InternedSeed::in_pool(custom)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_ne!(deserialized.as_ptr(), method.as_ptr());
+        assert_eq!(
+            deserialized.as_ptr(),
+            Interned::new_in(custom, b"GET").as_ptr()
+        );
+    }
+    pool::clear_thread_cache();
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "json")]
+fn json_value() {
+    {
+        use crate::json::Value;
+
+        let text = r#"{"a":[1,-2,3.5,true,null,"x"],"b":{"a":"a"}}"#;
+        let value = serde_json::from_str::<Value>(text).expect("deserialize");
+        assert_eq!(serde_json::to_string(&value).unwrap(), text);
+
+        let a = value.get("a").unwrap();
+        let nested = value.get("b").unwrap();
+        assert_eq!(nested.get("a").unwrap().as_str().unwrap().to_string(), "a");
+        assert!(value.get("missing").is_none());
+        assert!(a.get("a").is_none());
+
+        // the same string as a key and as a value shares its entry
+        let Value::Object(map) = nested else {
+            panic!("expected an object");
+        };
+        let (key, inner) = map.iter().next().unwrap();
+        assert_eq!(key.as_ptr(), inner.as_str().unwrap().as_ptr());
+
+        let json = serde_json::from_str::<serde_json::Value>(text).unwrap();
+        assert_eq!(serde_json::Value::from(&value), json);
+        assert_eq!(Value::from(json), value);
+    }
+    verify_empty();
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "serde")]
+fn serde_borrowed() {
+    {
+        use crate::InternedStr;
+
+        let text = Interned::new(b"hello");
+        let binary = Interned::new(b"\xff");
+        let borrowed: &BorrowedInterned = &text;
+        assert_eq!(serde_json::to_string(borrowed).unwrap(), r#""hello""#);
+        assert_eq!(
+            serde_json::to_string(&*binary).unwrap(),
+            serde_json::to_string(&binary).unwrap()
+        );
+        assert_eq!(rmp_serde::to_vec(&*binary).unwrap(), b"\xc4\x01\xff");
+
+        let s = InternedStr::new("hello");
+        assert_eq!(serde_json::to_string(&*s).unwrap(), r#""hello""#);
+    }
+    verify_empty();
 }
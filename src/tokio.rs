@@ -0,0 +1,102 @@
+use std::io;
+
+use ::bytes::{BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+
+use crate::{borrow::BorrowedInterned, interned::Interned};
+
+/// The default for [InternedCodec::max_frame_length], same as [LengthDelimitedCodec]'s
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// A codec for frames prefixed by their length as a big-endian `u32`, decoding each frame into
+/// an [Interned]
+///
+/// Frames are interned straight from the read buffer, without copying them into a buffer of
+/// their own first
+///
+/// # Example
+///
+/// ```
+/// use bytes::BytesMut;
+/// use intern_mint::{Interned, tokio::InternedCodec};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = InternedCodec::new();
+/// let mut buffer = BytesMut::new();
+/// codec.encode(&Interned::new(b"frame"), &mut buffer).unwrap();
+///
+/// assert_eq!(&buffer[..], b"\0\0\0\x05frame");
+/// assert!(codec.decode(&mut buffer).unwrap().unwrap() == Interned::new(b"frame"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InternedCodec {
+    inner: LengthDelimitedCodec,
+    max_frame_length: usize,
+}
+
+impl InternedCodec {
+    /// Constructs a new [InternedCodec] accepting frames of up to [DEFAULT_MAX_FRAME_LENGTH]
+    /// bytes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximal length of frames, longer frames fail to decode and encode
+    pub fn max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.inner.set_max_frame_length(max_frame_length);
+        self.max_frame_length = max_frame_length;
+        self
+    }
+}
+
+impl Default for InternedCodec {
+    fn default() -> Self {
+        Self {
+            inner: LengthDelimitedCodec::builder()
+                .max_frame_length(DEFAULT_MAX_FRAME_LENGTH)
+                .new_codec(),
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+        }
+    }
+}
+
+impl Decoder for InternedCodec {
+    type Item = Interned;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self.inner.decode(src)?.map(|o| Interned::new(&o)))
+    }
+}
+
+impl Encoder<&BorrowedInterned> for InternedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &BorrowedInterned, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let len = u32::try_from(item.len())
+            .ok()
+            .filter(|&o| o as usize <= self.max_frame_length)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "frame is too long"))?;
+
+        dst.reserve(size_of::<u32>() + item.len());
+        dst.put_u32(len);
+        dst.extend_from_slice(item);
+        Ok(())
+    }
+}
+
+impl Encoder<&Interned> for InternedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &Interned, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode(&**item, dst)
+    }
+}
+
+impl Encoder<Interned> for InternedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Interned, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode(&*item, dst)
+    }
+}
@@ -0,0 +1,101 @@
+//! Records [tracing] events for interning operations that are worth a closer look
+//!
+//! - A `WARN` event when locking a shard of a pool takes longer than
+//!   [set_slow_lock_threshold] (one millisecond by default)
+//! - A `DEBUG` event when inserting an entry whose data is at least
+//!   [set_large_insert_threshold] bytes long (64KiB by default)
+//!
+//! Uncontended locks are acquired without measuring time, so the common path stays as fast as
+//! without this feature
+//!
+//! Events are only recorded once the shard is unlocked again, so subscribers may intern values
+//! themselves, and their work isn't added to the time the shard is locked
+
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+static SLOW_LOCK_THRESHOLD_NANOS: AtomicU64 = AtomicU64::new(1_000_000);
+static LARGE_INSERT_THRESHOLD: AtomicUsize = AtomicUsize::new(64 * 1024);
+
+/// Sets the duration above which waiting for a shard's lock is recorded
+pub fn set_slow_lock_threshold(threshold: Duration) {
+    SLOW_LOCK_THRESHOLD_NANOS.store(
+        threshold.as_nanos().try_into().unwrap_or(u64::MAX),
+        Ordering::Relaxed,
+    );
+}
+
+/// Sets the length from which inserting an entry is recorded
+pub fn set_large_insert_threshold(len: usize) {
+    LARGE_INSERT_THRESHOLD.store(len, Ordering::Relaxed);
+}
+
+/// An event that happened while a shard was locked, recorded by [emit_pending]
+enum Event {
+    SlowLock { shard: usize, elapsed: Duration },
+    LargeInsert { size: usize },
+}
+
+thread_local! {
+    static PENDING: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+/// `try_lock` and `lock` lock the shard at `index`, either shared or exclusively
+pub(crate) fn lock_shard<G>(
+    try_lock: impl FnOnce() -> Option<G>,
+    lock: impl FnOnce() -> G,
+    index: usize,
+) -> G {
+    if let Some(guard) = try_lock() {
+        return guard;
+    }
+
+    let start = Instant::now();
+    let guard = lock();
+    let elapsed = start.elapsed();
+
+    if elapsed.as_nanos() > u128::from(SLOW_LOCK_THRESHOLD_NANOS.load(Ordering::Relaxed)) {
+        let event = Event::SlowLock {
+            shard: index,
+            elapsed,
+        };
+        record(event);
+    }
+
+    guard
+}
+
+/// Called under the lock of the shard the entry is inserted to
+pub(crate) fn record_insert(size: usize) {
+    if size >= LARGE_INSERT_THRESHOLD.load(Ordering::Relaxed) {
+        record(Event::LargeInsert { size });
+    }
+}
+
+/// Events of entries dropped while the thread exits (e.g. by the cache of [intern_cached]) are
+/// not recorded
+///
+/// [intern_cached]: crate::pool::intern_cached
+fn record(event: Event) {
+    let _ = PENDING.try_with(|o| o.borrow_mut().push(event));
+}
+
+/// Records the events of the current thread, called right after a shard is unlocked
+pub(crate) fn emit_pending() {
+    let events = PENDING
+        .try_with(|o| std::mem::take(&mut *o.borrow_mut()))
+        .unwrap_or_default();
+    for event in events {
+        match event {
+            Event::SlowLock { shard, elapsed } => {
+                tracing::warn!(shard, ?elapsed, "slow shard lock acquisition");
+            }
+            Event::LargeInsert { size } => {
+                tracing::debug!(size, "large entry inserted to the pool");
+            }
+        }
+    }
+}
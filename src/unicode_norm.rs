@@ -0,0 +1,58 @@
+use std::ops::Deref;
+
+use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick};
+
+use crate::{interned::Interned, pool};
+
+impl Interned {
+    /// Constructs a new [Interned] for the NFC normalized form of `value`, so visually identical
+    /// strings coming from different sources share the same entry
+    ///
+    /// Same as [pool::intern_nfc]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let composed = Interned::new_nfc("\u{e9}");
+    /// let decomposed = Interned::new_nfc("e\u{301}");
+    ///
+    /// assert_eq!(composed.as_ptr(), decomposed.as_ptr());
+    /// ```
+    pub fn new_nfc(value: &str) -> Self {
+        nfc(value)
+    }
+
+    /// Constructs an [Interned] of the NFC normalized form of the value
+    ///
+    /// Values that aren't valid UTF-8, or that are already normalized, are returned as is
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use intern_mint::Interned;
+    ///
+    /// let decomposed = Interned::from("e\u{301}");
+    ///
+    /// assert_eq!(decomposed.normalized().as_ptr(), Interned::from("\u{e9}").as_ptr());
+    /// ```
+    pub fn normalized(&self) -> Interned {
+        match std::str::from_utf8(self.deref()) {
+            Ok(o) if is_nfc_quick(o.chars()) != IsNormalized::Yes => pool::intern_nfc(o),
+            _ => self.clone(),
+        }
+    }
+}
+
+pub(crate) fn nfc(value: &str) -> Interned {
+    if is_nfc_quick(value.chars()) == IsNormalized::Yes {
+        return value.into();
+    }
+
+    pool::with_buffer(|buffer| {
+        for c in value.nfc() {
+            buffer.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+    })
+}
@@ -0,0 +1,144 @@
+use std::{
+    any::{Any, TypeId},
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::LazyLock,
+};
+
+use parking_lot::RwLock;
+use triomphe::Arc;
+
+use crate::pool::ShardedSet;
+
+/// Returns the pool of the values of type `T`, creating it on first use
+///
+/// Pools are created once per type and live forever, same as the pool of [Interned]
+///
+/// [Interned]: crate::Interned
+pub(crate) fn pool_of<T: Hash + Eq + Send + Sync + 'static>() -> &'static ShardedSet<Arc<T>> {
+    type Pools = HashMap<TypeId, &'static (dyn Any + Send + Sync)>;
+    static POOLS: LazyLock<RwLock<Pools>> = LazyLock::new(Default::default);
+
+    let id = TypeId::of::<T>();
+
+    // the read guard must be released before taking the write lock
+    let existing = POOLS.read().get(&id).copied();
+    let pool = match existing {
+        Some(pool) => pool,
+        None => *POOLS
+            .write()
+            .entry(id)
+            .or_insert_with(|| Box::leak(Box::<ShardedSet<Arc<T>>>::default())),
+    };
+
+    pool.downcast_ref()
+        .expect("pools are keyed by the type of their values")
+}
+
+/// Interns values of any type, the same way [Interned] interns byte slices
+///
+/// Each type has its own pool, sharded the same way as the pool of [Interned]
+///
+/// Note that the pointer is being used for hashing and comparing for equality, the same as
+/// [Interned]
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::InternedValue;
+///
+/// let a = InternedValue::new((1, "one"));
+/// let b = InternedValue::new((1, "one"));
+///
+/// assert_eq!(a, b);
+/// assert!(std::ptr::eq(&*a, &*b));
+/// ```
+///
+/// [Interned]: crate::Interned
+pub struct InternedValue<T: Hash + Eq + Send + Sync + 'static>(Arc<T>);
+
+impl<T: Hash + Eq + Send + Sync + 'static> InternedValue<T> {
+    /// Constructs a new [InternedValue] for a given `value`
+    ///
+    /// `value` is moved into the pool if it doesn't exist there yet, and dropped otherwise
+    pub fn new(value: T) -> Self {
+        Self(
+            pool_of::<T>()
+                .get_or_insert_with(value, |o, _| Arc::new(o))
+                .expect("pools of values have no memory limit"),
+        )
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static> Clone for InternedValue<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static> Drop for InternedValue<T> {
+    fn drop(&mut self) {
+        pool_of::<T>().remove_if_needed(&self.0);
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static> Deref for InternedValue<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.deref()
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static> PartialEq for InternedValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static> Eq for InternedValue<T> {}
+
+impl<T: Hash + Eq + Send + Sync + 'static> Hash for InternedValue<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
+}
+
+impl<T: Hash + Ord + Send + Sync + 'static> PartialOrd for InternedValue<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Hash + Ord + Send + Sync + 'static> Ord for InternedValue<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static> From<T> for InternedValue<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static + Default> Default for InternedValue<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static + Display> Display for InternedValue<T> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Display::fmt(self.deref(), f)
+    }
+}
+
+impl<T: Hash + Eq + Send + Sync + 'static + Debug> Debug for InternedValue<T> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Debug::fmt(self.deref(), f)
+    }
+}
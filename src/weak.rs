@@ -0,0 +1,83 @@
+use std::{
+    fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+
+use crate::{interned::Interned, pool::Pool};
+
+/// A handle to an interned value that doesn't keep it in the pool, returned by
+/// [Interned::downgrade]
+///
+/// Upgrading it returns the same entry as long as any [Interned] of it is still alive, which
+/// allows side tables keyed by interned values without preventing their removal from the pool
+///
+/// The entry is found again by the address, length and hash of its data - the data itself isn't
+/// compared - so a [WeakInterned] of a removed entry may be equal to, and upgrade to, an entry
+/// allocated later at the same address with the same length and hash, which is almost always but
+/// not necessarily an entry of the same data
+///
+/// # Example
+///
+/// ```
+/// use intern_mint::Interned;
+///
+/// let interned = Interned::new(b"hello");
+/// let weak = interned.downgrade();
+///
+/// assert_eq!(weak.upgrade().unwrap().as_ptr(), interned.as_ptr());
+///
+/// drop(interned);
+/// assert!(weak.upgrade().is_none());
+/// ```
+#[derive(Clone, Copy)]
+pub struct WeakInterned {
+    pool: &'static Pool,
+    hash: u64,
+    addr: usize,
+    len: usize,
+}
+
+impl WeakInterned {
+    pub(crate) fn new(value: &Interned) -> Self {
+        Self {
+            pool: value.pool(),
+            hash: value.header().hash,
+            addr: value.as_ptr() as usize,
+            len: value.len(),
+        }
+    }
+
+    /// Returns the entry if it's still in its pool, or an entry that replaced it as described in
+    /// [WeakInterned]
+    ///
+    /// Values that were not kept in the pool (see [PoolBuilder::on_overflow]) are never upgraded
+    ///
+    /// [PoolBuilder::on_overflow]: crate::pool::PoolBuilder::on_overflow
+    pub fn upgrade(&self) -> Option<Interned> {
+        self.pool.get_by_address(self.hash, self.addr, self.len)
+    }
+}
+
+impl PartialEq for WeakInterned {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.pool, other.pool)
+            && (self.hash, self.addr, self.len) == (other.hash, other.addr, other.len)
+    }
+}
+
+impl Eq for WeakInterned {}
+
+impl Hash for WeakInterned {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr.hash(state);
+    }
+}
+
+impl Debug for WeakInterned {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.debug_struct("WeakInterned")
+            .field("addr", &(self.addr as *const u8))
+            .field("len", &self.len)
+            .finish()
+    }
+}